@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use rs_isl::*;
+
+const DIM: (usize, usize) = (120, 120);
+
+// Gray-Scott reaction diffusion, `u` diffuses on the Moore neighbourhood, `v` on the von Neumann neighbourhood
+const DU: f32 = 0.16;
+const DV: f32 = 0.08;
+const FEED: f32 = 0.035;
+const KILL: f32 = 0.065;
+
+fn main() {
+    let moore = vec![
+        (-1, 0),
+        (1, 0),
+        (0, -1),
+        (0, 1),
+        (-1, -1),
+        (1, -1),
+        (-1, 1),
+        (1, 1),
+    ];
+    let von_neumann = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    let seeded = |x: usize, y: usize| (x as isize - 60).abs() < 8 && (y as isize - 60).abs() < 8;
+
+    let mut sim = MultiField::new(DIM, ThreadExecutor::new(4), PathBuf::from("raw")).unwrap();
+    let u = sim.add_field(
+        "u",
+        move |x, y| if seeded(x, y) { 0.5f32 } else { 1.0 },
+        moore,
+    );
+    let v = sim.add_field(
+        "v",
+        move |x, y| if seeded(x, y) { 0.25f32 } else { 0.0 },
+        von_neumann,
+    );
+
+    // missing neighbours are treated like the cell itself, i.e. no flux across the boundary
+    let v_field = v.clone();
+    sim.set_operator(
+        &u,
+        Contextual(move |context: &Context, u: &f32, nb: Vec<Option<&f32>>| {
+            let (edges, corners) = nb.split_at(4);
+            let edges: f32 = edges.iter().map(|n| n.unwrap_or(u) - u).sum();
+            let corners: f32 = corners.iter().map(|n| n.unwrap_or(u) - u).sum();
+            let laplace = 0.2 * edges + 0.05 * corners;
+            let v = v_field.get(context, (0, 0)).unwrap();
+            u + 4.0 * DU * laplace - u * v * v + FEED * (1.0 - u)
+        }),
+    )
+    .unwrap();
+
+    let u_field = u.clone();
+    sim.set_operator(
+        &v,
+        Contextual(move |context: &Context, v: &f32, nb: Vec<Option<&f32>>| {
+            let laplace: f32 = nb.iter().map(|n| n.unwrap_or(v) - v).sum();
+            let u = u_field.get(context, (0, 0)).unwrap();
+            v + DV * laplace + u * v * v - (FEED + KILL) * v
+        }),
+    )
+    .unwrap();
+
+    // both fields are written into the same files, named "u" and "v"
+    sim.run(2000, 20);
+}
@@ -0,0 +1,51 @@
+use std::{env, fs, net::SocketAddr, path::PathBuf};
+
+use rs_isl::*;
+
+const DIM: (usize, usize) = (200, 100);
+const FIRST_PORT: u16 = 7000;
+
+// Diffusion of a hot spot, split over several processes on one host.
+// Start every rank in its own terminal, e.g. for two processes:
+//   cargo run --example distributed -- 0 2
+//   cargo run --example distributed -- 1 2
+// and open raw/distributed/ISL.pvd in ParaView.
+fn main() {
+    let mut args = env::args().skip(1).map(|arg| arg.parse::<usize>().unwrap());
+    let (rank, size) = (args.next().unwrap(), args.next().unwrap());
+
+    let addresses: Vec<SocketAddr> = (0..size)
+        .map(|rank| SocketAddr::from(([127, 0, 0, 1], FIRST_PORT + rank as u16)))
+        .collect();
+    let transport = SocketTransport::tcp(rank, &addresses).unwrap();
+
+    let op = |num: &f32, nb: Vec<Option<&f32>>| {
+        let sum: f32 = nb.iter().map(|value| value.copied().unwrap_or(*num)).sum();
+        0.6 * num + 0.1 * sum
+    };
+    let init = |x: usize, y: usize| {
+        if (x as isize - 100).abs() < 10 && (y as isize - 50).abs() < 10 {
+            100.0f32
+        } else {
+            0.0
+        }
+    };
+
+    let output_path = PathBuf::from("raw/distributed");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = IslParams::new(
+        DIM,
+        op,
+        1,
+        init,
+        200,
+        20,
+        vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+        output_path,
+    )
+    .with_decomposition(Decomposition::Auto);
+
+    let mut sim = DistributedSimulation::new(params, transport).unwrap();
+    sim.run().unwrap();
+    println!("rank {rank} computed {:?}", sim.region());
+}
@@ -0,0 +1,21 @@
+use std::{fs, path::PathBuf};
+
+use rs_isl::{models::*, run_isl};
+
+const DIM: (usize, usize) = (128, 128);
+
+// Gray-Scott stripes growing from a small seeded square, see `coupled_fields` for the same model on two fields.
+fn main() {
+    let init = |x: usize, y: usize| {
+        if (59..69).contains(&x) && (59..69).contains(&y) {
+            Chemicals { u: 0.5, v: 0.25 }
+        } else {
+            Chemicals::default()
+        }
+    };
+
+    let output_path = PathBuf::from("raw/gray_scott");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = GrayScott::coral().params(DIM, 4, init, 5000, 50, output_path);
+    run_isl(params).unwrap();
+}
@@ -0,0 +1,28 @@
+use std::{fs, path::PathBuf};
+
+use rs_isl::{models::*, run_isl};
+
+const DIM: (usize, usize) = (100, 100);
+
+// A hot square cooling down in a plate whose edges are held at 0.
+fn main() {
+    let init = |x: usize, y: usize| {
+        if (40..60).contains(&x) && (40..60).contains(&y) {
+            100.0
+        } else {
+            0.0
+        }
+    };
+
+    let output_path = PathBuf::from("raw/heat");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = Heat::new(0.2).with_boundary(Boundary::Fixed(0.0)).params(
+        DIM,
+        4,
+        init,
+        1000,
+        50,
+        output_path,
+    );
+    run_isl(params).unwrap();
+}
@@ -0,0 +1,40 @@
+use std::{fs, path::PathBuf};
+
+use rs_isl::*;
+
+const DIM: (usize, usize) = (128, 128);
+// below the critical temperature of about 2.27, domains of equal spin grow over time
+const TEMPERATURE: f64 = 2.0;
+
+// Metropolis sampling of the 2d Ising model. Red and black cells are updated in turns,
+// so no two neighbouring spins flip at once. Every run with the same seed gives the same result.
+fn main() {
+    let neighbours = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    // flipping a spin changes the energy by 2 * s * (sum of the neighbouring spins),
+    // the flip is accepted with the probability min(1, exp(-dE / T))
+    let acceptance = |spin: &i8, nb: &[Option<&i8>]| {
+        let field: i8 = nb.iter().flatten().copied().sum();
+        let energy = 2.0 * (*spin as f64) * (field as f64);
+        (-energy / TEMPERATURE).exp().min(1.0)
+    };
+    let metropolis = WithProbability::varying(acceptance, |spin: &i8, _nb: Vec<Option<&i8>>| -spin);
+
+    // random initial spins
+    let init = |x: usize, y: usize| {
+        let hash = (x as u64 * 0x9e37_79b9 + y as u64 * 0x85eb_ca6b).wrapping_mul(0xc2b2_ae35);
+        if hash >> 40 & 1 == 0 {
+            1i8
+        } else {
+            -1
+        }
+    };
+
+    let output_path = PathBuf::from("raw/ising");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = IslParams::new(DIM, metropolis, 4, init, 500, 50, neighbours, output_path)
+        .with_update_order(UpdateOrder::RedBlack)
+        .with_seed(2024);
+
+    run_isl(params).unwrap();
+}
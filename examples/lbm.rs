@@ -0,0 +1,55 @@
+use std::{env, fs, path::PathBuf, time::Instant};
+
+use rs_isl::{lbm::*, Simulation};
+
+const DIM: (usize, usize) = (400, 100);
+const INFLOW: f32 = 0.05;
+const CYLINDER: (f32, f32, f32) = (80.0, 52.0, 10.0);
+
+// Flow past a cylinder at a Reynolds number of 100, which sheds a Kármán vortex street.
+// Also measures the speed of the engine in million lattice updates per second (MLUPS),
+// pass the number of runners as the first argument, e.g.
+//   cargo run --release --example lbm -- 8
+fn main() {
+    let runners = env::args().nth(1).map_or(4, |arg| arg.parse().unwrap());
+
+    // Re = u * d / viscosity
+    let viscosity = INFLOW * 2.0 * CYLINDER.2 / 100.0;
+    let lattice = Lattice::new(DIM, viscosity)
+        .with_edge(Side::Left, Edge::Velocity(INFLOW, 0.0))
+        .with_edge(Side::Right, Edge::Outflow);
+
+    let fluid = |x: usize, y: usize| {
+        let (cx, cy, r) = CYLINDER;
+        (x as f32 - cx).powi(2) + (y as f32 - cy).powi(2) > r * r
+    };
+
+    let output_path = PathBuf::from("raw/lbm");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = lattice
+        .params(
+            runners,
+            |_, _| Distributions::equilibrium(1.0, (INFLOW, 0.0)),
+            20000,
+            100,
+            output_path,
+        )
+        .with_mask(fluid);
+
+    let mut sim = Simulation::new(params).unwrap();
+    let start = Instant::now();
+    sim.run();
+    let seconds = start.elapsed().as_secs_f64();
+
+    // the cells inside the cylinder are masked and not updated
+    let active = (0..DIM.1)
+        .flat_map(|y| (0..DIM.0).map(move |x| (x, y)))
+        .filter(|&(x, y)| fluid(x, y))
+        .count();
+    let updates = (active * sim.steps_done()) as f64;
+    println!(
+        "{} steps on {runners} runners in {seconds:.1} s, {:.1} MLUPS including output",
+        sim.steps_done(),
+        updates / seconds / 1e6
+    );
+}
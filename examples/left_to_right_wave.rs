@@ -15,7 +15,7 @@ fn main() {
             let f = *nb[0].unwrap();
             return f;
         }
-        return max(*num as i32 - 3, 0) as f32;
+        max(*num as i32 - 3, 0) as f32
     };
 
     // creates a sine shape at the left boundary of the domain
@@ -31,7 +31,7 @@ fn main() {
     let params = IslParams::new(
         DIM,
         op,
-        // number of threads for simulation, the domain size must be divisible by this number
+        // number of threads for simulation, every thread needs at least one row and column of the domain
         100,
         init,
         // number of simulation steps
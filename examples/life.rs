@@ -0,0 +1,33 @@
+use std::{fs, path::PathBuf};
+
+use rs_isl::{models::*, run_isl};
+
+const DIM: (usize, usize) = (120, 80);
+
+// A Gosper glider gun in Conway's Game of Life, emitting a glider every 30 steps.
+// Other Life-like rules can be given in the B/S notation, e.g. "B36/S23".
+fn main() {
+    let gun = [
+        "........................O",
+        "......................O.O",
+        "............OO......OO............OO",
+        "...........O...O....OO............OO",
+        "OO........O.....O...OO",
+        "OO........O...O.OO....O.O",
+        "..........O.....O.......O",
+        "...........O...O",
+        "............OO",
+    ];
+    let init = move |x: usize, y: usize| {
+        let (x, y) = (x.wrapping_sub(5), y.wrapping_sub(5));
+        gun.get(y)
+            .and_then(|row| row.as_bytes().get(x))
+            .is_some_and(|cell| *cell == b'O')
+    };
+
+    let rule: Life = "B3/S23".parse().unwrap();
+    let output_path = PathBuf::from("raw/life");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = rule.params(DIM, 4, init, 300, 100, output_path);
+    run_isl(params).unwrap();
+}
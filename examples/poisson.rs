@@ -0,0 +1,23 @@
+use std::{f32::consts::PI, fs, path::PathBuf};
+
+use rs_isl::{models::*, run_isl, UpdateOrder};
+
+const N: usize = 64;
+
+// Solves laplace(u) = -2π² sin(πx) sin(πy) on the unit square with u = 0 on the boundary,
+// whose solution is u = sin(πx) sin(πy). The cells lie at the interior points (x + 1) * h.
+fn main() {
+    let h = 1.0 / (N + 1) as f32;
+    let position = move |i: usize| (i + 1) as f32 * h;
+    let source = move |x: usize, y: usize| {
+        -2.0 * PI * PI * (PI * position(x)).sin() * (PI * position(y)).sin()
+    };
+
+    let output_path = PathBuf::from("raw/poisson");
+    fs::create_dir_all(&output_path).unwrap();
+    // red-black ordering turns the Jacobi iteration into Gauss-Seidel
+    let params = Jacobi::poisson(source, h)
+        .params((N, N), 4, |_, _| 0.0, 4000, 10, output_path)
+        .with_update_order(UpdateOrder::RedBlack);
+    run_isl(params).unwrap();
+}
@@ -10,8 +10,8 @@ struct CellData {
 }
 
 impl VtkOutput for CellData {
-    fn cellvalue(&self) -> Vec<f32> {
-        vec![self.horizontal as f32, self.vertical as f32]
+    fn cellvalue(&self) -> Vec<Value> {
+        vec![self.horizontal.into(), self.vertical.into()]
     }
 
     fn value_names() -> Vec<String> {
@@ -66,7 +66,7 @@ fn main() {
     let params = IslParams::new(
         DIM,
         op,
-        // number of threads for simulation, the domain size must be divisible by this number
+        // number of threads for simulation, every thread needs at least one row and column of the domain
         1,
         init,
         // number of simulation steps
@@ -0,0 +1,18 @@
+use std::{fs, path::PathBuf};
+
+use rs_isl::{models::*, run_isl};
+
+const DIM: (usize, usize) = (200, 200);
+
+// A drop falling onto a clamped membrane, the ring wave is reflected at the edges.
+fn main() {
+    let init = |x: usize, y: usize| {
+        let r2 = (x as f32 - 70.0).powi(2) + (y as f32 - 100.0).powi(2);
+        WaveCell::at_rest(5.0 * (-r2 / 20.0).exp())
+    };
+
+    let output_path = PathBuf::from("raw/wave");
+    fs::create_dir_all(&output_path).unwrap();
+    let params = Wave::new(0.5).params(DIM, 4, init, 600, 60, output_path);
+    run_isl(params).unwrap();
+}
@@ -0,0 +1,212 @@
+//! Derive macro for `rs_isl::VtkOutput`, enabled by the `derive` feature of rs_isl.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Expr, ExprLit, Index, Lit,
+    LitStr, Member, Type,
+};
+
+/// Derives `VtkOutput` for a struct, writing one output value per field.
+///
+/// A field of a type with a single output value, e.g. `f64`, is written under the name of the field.
+/// A field of a type with several output values, e.g. another struct deriving `VtkOutput`,
+/// is written as `field.value` for each of them. Fields of tuple structs are named by their index.
+///
+/// Fields may be marked with
+/// - `#[vtk(rename = "name")]` to write the field under another name
+/// - `#[vtk(skip)]` to leave the field out of the output
+/// - `#[vtk(vector)]` to write an array or tuple of 2 or 3 values as a vector
+#[proc_macro_derive(VtkOutput, attributes(vtk))]
+pub fn derive_vtk_output(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How a field is written
+enum Output {
+    /// Through the `VtkOutput` implementation of its type
+    Nested,
+    /// As a vector of the given components
+    Vector(Vec<TokenStream2>),
+}
+
+struct Column {
+    name: String,
+    member: Member,
+    ty: Type,
+    output: Output,
+}
+
+fn expand(mut input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "VtkOutput can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        let mut name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+        let (mut skip, mut vector) = (false, false);
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("vtk"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else if meta.path.is_ident("vector") {
+                    vector = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `skip` or `vector`"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if skip {
+            continue;
+        }
+        let output = if vector {
+            Output::Vector(components(&member, &field.ty)?)
+        } else {
+            Output::Nested
+        };
+        columns.push(Column {
+            name,
+            member,
+            ty: field.ty.clone(),
+            output,
+        });
+    }
+
+    // every nested type has to implement VtkOutput itself
+    let where_clause = input.generics.make_where_clause();
+    for column in &columns {
+        if let Output::Nested = column.output {
+            let ty = &column.ty;
+            where_clause
+                .predicates
+                .push(syn::parse_quote!(#ty: ::rs_isl::VtkOutput));
+        }
+    }
+
+    let mut names = vec![];
+    let mut kinds = vec![];
+    let mut values = vec![];
+    for Column {
+        name,
+        member,
+        ty,
+        output,
+    } in &columns
+    {
+        match output {
+            Output::Nested => {
+                names.push(quote! {
+                    let inner = <#ty as ::rs_isl::VtkOutput>::value_names();
+                    if inner.len() == 1 {
+                        names.push(#name.to_string());
+                    } else {
+                        names.extend(inner.into_iter().map(|inner| ::std::format!("{}.{}", #name, inner)));
+                    }
+                });
+                kinds.push(quote! {
+                    kinds.extend(<#ty as ::rs_isl::VtkOutput>::value_kinds());
+                });
+                values.push(quote! {
+                    values.extend(::rs_isl::VtkOutput::cellvalue(&self.#member));
+                });
+            }
+            Output::Vector(components) => {
+                let len = components.len();
+                names.push(quote! {
+                    names.push(#name.to_string());
+                });
+                kinds.push(quote! {
+                    kinds.push(::rs_isl::ValueKind::Vector(#len));
+                });
+                values.push(quote! {
+                    #(values.push(::rs_isl::Value::from(::core::clone::Clone::clone(&#components)));)*
+                });
+            }
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rs_isl::VtkOutput for #ident #ty_generics #where_clause {
+            fn value_names() -> ::std::vec::Vec<::std::string::String> {
+                let mut names = ::std::vec::Vec::new();
+                #(#names)*
+                names
+            }
+
+            fn value_kinds() -> ::std::vec::Vec<::rs_isl::ValueKind> {
+                let mut kinds = ::std::vec::Vec::new();
+                #(#kinds)*
+                kinds
+            }
+
+            fn cellvalue(&self) -> ::std::vec::Vec<::rs_isl::Value> {
+                let mut values = ::std::vec::Vec::new();
+                #(#values)*
+                values
+            }
+        }
+    })
+}
+
+/// The components of a vector field, which has to be an array or a tuple of 2 or 3 values
+fn components(member: &Member, ty: &Type) -> syn::Result<Vec<TokenStream2>> {
+    let error = || {
+        Error::new(
+            ty.span(),
+            "vectors must be arrays or tuples of 2 or 3 values",
+        )
+    };
+    let components: Vec<TokenStream2> = match ty {
+        Type::Array(array) => {
+            let Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            }) = &array.len
+            else {
+                return Err(error());
+            };
+            (0..len.base10_parse::<usize>()?)
+                .map(|i| quote!(self.#member[#i]))
+                .collect()
+        }
+        Type::Tuple(tuple) => (0..tuple.elems.len())
+            .map(|i| {
+                let i = Index::from(i);
+                quote!(self.#member.#i)
+            })
+            .collect(),
+        _ => return Err(error()),
+    };
+    if !(2..=3).contains(&components.len()) {
+        return Err(error());
+    }
+    Ok(components)
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use crate::{
+    grid::IslError,
+    operator::{Context, Operator},
+};
+
+/// The stream of [Context::rng_stream] a [WithProbability] draws from unless told otherwise
+const PROBABILITY_STREAM: u64 = u64::MAX;
+
+type Probability<T> = Arc<dyn Fn(&T, &[Option<&T>]) -> f64 + Send + Sync>;
+
+/// Counts the neighbours which exist and fulfil `predicate`, e.g. the burning trees around a cell.
+///
+/// # Example
+/// ```rust
+/// use rs_isl::*;
+///
+/// let game_of_life = |alive: &bool, nb: Vec<Option<&bool>>| {
+///     matches!((alive, count(&nb, |alive| *alive)), (true, 2 | 3) | (false, 3))
+/// };
+/// ```
+pub fn count<T>(neighbours: &[Option<&T>], predicate: impl Fn(&T) -> bool) -> usize {
+    neighbours
+        .iter()
+        .flatten()
+        .filter(|value| predicate(value))
+        .count()
+}
+
+/// An operator applying another one only with some probability, otherwise the cell keeps its value.
+///
+/// The probability can be fixed or depend on the cell and its neighbours, e.g. the Metropolis acceptance of a spin flip.
+/// Whether the rule is applied is decided by the random numbers of the cell, see [Context::rng],
+/// so the results are the same for any number of runners. The wrapped operator can draw its own numbers from [Context::rng].
+///
+/// # Example
+/// ```rust
+/// use rs_isl::*;
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Tree {
+///     Empty,
+///     Growing,
+///     Burning,
+/// }
+///
+/// // forest fire: trees grow with probability 0.05 on empty cells, burning trees burn down
+/// let grow = WithProbability::new(0.05, |tree: &Tree, _nb: Vec<Option<&Tree>>| match tree {
+///     Tree::Empty => Tree::Growing,
+///     _ => *tree,
+/// });
+/// let fire = Contextual(move |context: &Context, tree: &Tree, nb: Vec<Option<&Tree>>| match tree {
+///     Tree::Burning => Tree::Empty,
+///     Tree::Growing if count(&nb, |tree| *tree == Tree::Burning) > 0 => Tree::Burning,
+///     // lightning
+///     Tree::Growing if context.rng().chance(0.0001) => Tree::Burning,
+///     _ => grow.apply(context, tree, nb),
+/// });
+/// ```
+pub struct WithProbability<T, F> {
+    probability: Probability<T>,
+    op: F,
+    stream: u64,
+}
+
+impl<T, F> Clone for WithProbability<T, F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            probability: self.probability.clone(),
+            op: self.op.clone(),
+            stream: self.stream,
+        }
+    }
+}
+
+impl<T, F> WithProbability<T, F>
+where
+    F: Operator<T>,
+{
+    /// Applies `op` to every cell with the probability `probability`
+    pub fn new(probability: f64, op: F) -> Self {
+        Self::varying(move |_, _| probability, op)
+    }
+
+    /// Applies `op` with the probability calculated from the value of the cell and its neighbours
+    pub fn varying(
+        probability: impl Fn(&T, &[Option<&T>]) -> f64 + Send + Sync + 'static,
+        op: F,
+    ) -> Self {
+        Self {
+            probability: Arc::new(probability),
+            op,
+            stream: PROBABILITY_STREAM,
+        }
+    }
+
+    /// Draws from the given stream of [Context::rng_stream], for nesting several of these operators
+    pub fn with_stream(mut self, stream: u64) -> Self {
+        self.stream = stream;
+        self
+    }
+}
+
+impl<T, F> Operator<T> for WithProbability<T, F>
+where
+    T: Clone,
+    F: Operator<T>,
+{
+    fn apply(&self, context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        let probability = (self.probability)(value, &neighbours);
+        if context.rng_stream(self.stream).chance(probability) {
+            self.op.apply(context, value, neighbours)
+        } else {
+            value.clone()
+        }
+    }
+
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        self.op.offsets()
+    }
+
+    fn check(&self, dimension: (usize, usize)) -> Result<(), IslError> {
+        self.op.check(dimension)
+    }
+}
+
+/// An operator setting every cell to the most common value among itself and its neighbours.
+///
+/// If several values are the most common, the cell keeps its value when it is one of them,
+/// otherwise it takes the first of them in the order of the neighbours.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Majority;
+
+impl<T> Operator<T> for Majority
+where
+    T: Clone + PartialEq,
+{
+    fn apply(&self, _context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        let candidates: Vec<&T> = std::iter::once(value)
+            .chain(neighbours.iter().flatten().copied())
+            .collect();
+        let votes = |candidate: &T| {
+            candidates
+                .iter()
+                .filter(|other| **other == candidate)
+                .count()
+        };
+
+        let mut winner = (value, votes(value));
+        for candidate in &candidates[1..] {
+            let votes = votes(candidate);
+            if votes > winner.1 {
+                winner = (candidate, votes);
+            }
+        }
+        winner.0.clone()
+    }
+}
+
+/// An operator switching cells to `state` once at least `threshold` of their neighbours are in it.
+///
+/// Cells in `state` stay there, all other cells keep their value until the threshold is reached,
+/// e.g. for bootstrap percolation or the spreading of an opinion.
+#[derive(Clone, Debug)]
+pub struct Threshold<T> {
+    state: T,
+    threshold: usize,
+}
+
+impl<T> Threshold<T> {
+    pub fn new(state: T, threshold: usize) -> Self {
+        Self { state, threshold }
+    }
+}
+
+impl<T> Operator<T> for Threshold<T>
+where
+    T: Clone + PartialEq + Send + Sync,
+{
+    fn apply(&self, _context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        if count(&neighbours, |neighbour| *neighbour == self.state) >= self.threshold {
+            self.state.clone()
+        } else {
+            value.clone()
+        }
+    }
+}
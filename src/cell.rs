@@ -2,22 +2,30 @@ use std::{mem::swap, sync::Arc};
 
 use parking_lot::RwLock;
 
-use crate::withcall::WithCall;
+use crate::{
+    integrator::{AddScaled, Integrator},
+    operator::{Context, Operator},
+};
 
 #[derive(Clone)]
 pub struct Cell<T> {
     pub value: Arc<RwLock<T>>,
     pub neighbours: Vec<Option<Arc<RwLock<T>>>>,
     pub next_val: T,
+    pub colour: usize,
+    /// Position of the cell, (x,y)
+    pub position: (usize, usize),
+    /// The value at the start of the step, followed by the derivatives of the finished stages
+    pub stages: Vec<T>,
 }
 
 impl<T> Cell<T>
 where
     T: Clone,
 {
-    pub fn run<F>(&mut self, op: &WithCall<F, T>)
+    pub fn run<O>(&mut self, op: &O, context: Context)
     where
-        F: Fn(&T, Vec<Option<&T>>) -> T,
+        O: Operator<T> + ?Sized,
     {
         let mut locks = vec![];
         let mut nbs = vec![];
@@ -40,11 +48,36 @@ where
             nbs.push(inner);
         }
 
-        self.next_val = op.run(own_ref, nbs);
+        let context = context.at(self.position.0, self.position.1);
+        self.next_val = op.apply(&context, own_ref, nbs);
     }
 
     pub fn write(&mut self) {
         let mut lt = self.value.write();
         swap(&mut *lt, &mut self.next_val);
     }
+
+    /// Keeps the derivative computed in this stage and writes the value the next stage starts from
+    pub fn integrate(
+        &mut self,
+        integrator: &Integrator,
+        stage: usize,
+        dt: f64,
+        add_scaled: AddScaled<T>,
+    ) {
+        let mut value = self.value.write();
+        if stage == 0 {
+            self.stages.clear();
+            self.stages.push(value.clone());
+        }
+        self.stages.push(self.next_val.clone());
+
+        let mut next = self.stages[0].clone();
+        for (derivative, weight) in self.stages[1..].iter().zip(integrator.weights(stage)) {
+            if *weight != 0.0 {
+                next = add_scaled(&next, derivative, dt * weight);
+            }
+        }
+        *value = next;
+    }
 }
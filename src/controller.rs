@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use parking_lot::{Condvar, Mutex, RwLock};
+
+use crate::view::{GridView, GridViewMut};
+
+pub(crate) type SharedGrid<T> = Arc<Vec<Vec<Arc<RwLock<T>>>>>;
+
+/// Handle for pausing a running simulation at a step boundary.
+///
+/// Pass a clone to [IslParams::with_controller](crate::IslParams::with_controller),
+/// run the simulation on another thread and use [Controller::pause] to park the runners
+/// between two steps. While paused the grid can be read and modified through the returned [PausedGrid].
+///
+/// # Example
+/// ```rust, no_run
+/// use std::{path::PathBuf, thread};
+///
+/// use rs_isl::*;
+///
+/// let controller = Controller::new();
+///
+/// let op = |num: &f32, _nb: Vec<Option<&f32>>| *num + 1.0;
+/// let params = IslParams::new((10, 10), op, 1, |_, _| 0.0, 100, 1, vec![], PathBuf::from("raw"))
+///     .with_controller(controller.clone());
+///
+/// let handle = thread::spawn(move || run_isl(params));
+///
+/// if let Some(mut grid) = controller.pause() {
+///     // inject a perturbation
+///     grid.set(5, 5, 100.0);
+/// } // dropping the paused grid resumes the runners
+///
+/// handle.join().unwrap().unwrap();
+/// ```
+pub struct Controller<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+    cond: Condvar,
+}
+
+struct State<T> {
+    pause_requested: bool,
+    paused: bool,
+    held: bool,
+    finished: bool,
+    step: usize,
+    grid: Option<SharedGrid<T>>,
+}
+
+impl<T> Clone for Controller<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for Controller<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Controller<T> {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    pause_requested: false,
+                    paused: false,
+                    held: false,
+                    finished: false,
+                    step: 0,
+                    grid: None,
+                }),
+                cond: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Requests a pause and blocks until all runners are parked at the next step boundary.
+    ///
+    /// The runners stay parked until the returned [PausedGrid] is dropped or [PausedGrid::resume] is called.
+    /// Returns `None` if the simulation finishes before it could be paused.
+    pub fn pause(&self) -> Option<PausedGrid<'_, T>> {
+        let mut state = self.inner.state.lock();
+
+        while !state.finished && (!state.paused || state.held) {
+            state.pause_requested = true;
+            self.inner.cond.wait(&mut state);
+        }
+
+        if state.finished {
+            return None;
+        }
+
+        state.held = true;
+
+        Some(PausedGrid {
+            controller: self,
+            grid: state.grid.clone().unwrap(),
+            step: state.step,
+        })
+    }
+
+    /// Whether the simulation this controller is attached to has finished
+    pub fn is_finished(&self) -> bool {
+        self.inner.state.lock().finished
+    }
+
+    pub(crate) fn start(&self, grid: SharedGrid<T>) {
+        let mut state = self.inner.state.lock();
+        state.grid = Some(grid);
+        state.finished = false;
+    }
+
+    /// Parks the calling thread while a pause is in effect. Must only be called while all runners are synchronized.
+    pub(crate) fn checkpoint(&self, step: usize) {
+        let mut state = self.inner.state.lock();
+        state.step = step;
+
+        if state.pause_requested {
+            state.paused = true;
+            self.inner.cond.notify_all();
+
+            while state.paused {
+                self.inner.cond.wait(&mut state);
+            }
+        }
+    }
+
+    pub(crate) fn finish(&self) {
+        let mut state = self.inner.state.lock();
+        state.finished = true;
+        state.grid = None;
+        self.inner.cond.notify_all();
+    }
+
+    /// Returns a guard which finishes the controller when dropped, also while unwinding from a panic
+    pub(crate) fn finish_on_drop(&self) -> FinishGuard<T> {
+        FinishGuard(self.clone())
+    }
+
+    fn resume(&self) {
+        let mut state = self.inner.state.lock();
+        state.pause_requested = false;
+        state.paused = false;
+        state.held = false;
+        self.inner.cond.notify_all();
+    }
+}
+
+/// Finishes its controller when dropped, so nobody waits for a pause of a simulation which has stopped
+pub(crate) struct FinishGuard<T>(Controller<T>);
+
+impl<T> Drop for FinishGuard<T> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+/// Mutable view of the grid, handed out by [Controller::pause] while the runners are parked.
+///
+/// Coordinates are given as (x, y), matching the `init` closure of [IslParams](crate::IslParams).
+pub struct PausedGrid<'a, T> {
+    controller: &'a Controller<T>,
+    grid: SharedGrid<T>,
+    step: usize,
+}
+
+impl<T> PausedGrid<'_, T> {
+    /// Number of steps completed before the pause
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Read access to the grid
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView::new(&self.grid)
+    }
+
+    /// Read and write access to the grid
+    pub fn view_mut(&mut self) -> GridViewMut<'_, T> {
+        GridViewMut::new(&self.grid)
+    }
+
+    /// The size of the grid, (x,y)
+    pub fn dimension(&self) -> (usize, usize) {
+        self.view().dimension()
+    }
+
+    /// Returns a copy of the value at (x,y)
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x,y) lies outside of the grid.
+    pub fn get(&self, x: usize, y: usize) -> T
+    where
+        T: Clone,
+    {
+        self.view().get(x, y)
+    }
+
+    /// Overwrites the value at (x,y)
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x,y) lies outside of the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.view_mut().set(x, y, value);
+    }
+
+    /// Modifies the value at (x,y) in place
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x,y) lies outside of the grid.
+    pub fn update(&mut self, x: usize, y: usize, f: impl FnOnce(&mut T)) {
+        self.view_mut().update(x, y, f);
+    }
+
+    /// Resumes the simulation, equivalent to dropping the paused grid
+    pub fn resume(self) {}
+}
+
+impl<T> Drop for PausedGrid<'_, T> {
+    fn drop(&mut self) {
+        self.controller.resume();
+    }
+}
@@ -0,0 +1,406 @@
+use std::{
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use crate::{
+    grid::IslError,
+    operator::{Context, Operator},
+    region::Region,
+    transport::{Encode, Transport},
+    vtk_writer::{self, Centring},
+    IslParams, VtkOutput,
+};
+
+/// A simulation which is split over several processes, e.g. on different machines.
+///
+/// Every process owns one block of the domain, arranged like the runner blocks of a [ThreadExecutor](crate::ThreadExecutor)
+/// with one runner per process, and computes it on a single thread.
+/// Before every step the processes exchange the halo of their blocks, the cells of other blocks within the
+/// reach of the neighbours, over a [Transport].
+///
+/// Of the [IslParams], the dimension, operator, initial values, steps, output steps, neighbours, output path,
+/// dt, seed, centring and decomposition are used. The number of runners, tiling, cost and rebalancing are ignored,
+/// settings which would change the results, like a mask, an integrator or a forcing, are rejected.
+/// Every process writes its block into `ISL{idx}_{rank}.vts`, which rank 0 combines
+/// into the parallel file `ISL{idx}.pvts` and the collection `ISL.pvd`.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// // started as `simulation <rank>` on one host
+/// let rank: usize = std::env::args().nth(1).unwrap().parse().unwrap();
+/// let addresses = vec!["127.0.0.1:7000".parse().unwrap(), "127.0.0.1:7001".parse().unwrap()];
+/// let transport = SocketTransport::tcp(rank, &addresses).unwrap();
+///
+/// let op = |num: &f32, nb: Vec<Option<&f32>>| nb[0].copied().unwrap_or(*num);
+/// let params = IslParams::new((200, 100), op, 1, |x, _| x as f32, 100, 10, vec![(-1, 0)], PathBuf::from("raw"));
+///
+/// let mut sim = DistributedSimulation::new(params, transport).unwrap();
+/// sim.run().unwrap();
+/// ```
+pub struct DistributedSimulation<T, F, C> {
+    transport: C,
+    op: F,
+    neighbours: Vec<(i8, i8)>,
+    dimension: (usize, usize),
+    /// The block owned by every rank
+    regions: Vec<Region>,
+    /// Reach of the halo along x and y, at least one cell for the shared points of the output
+    halo: (usize, usize),
+    /// The block of this rank together with its halo
+    local: Region,
+    /// Values of the local region, row by row
+    values: Vec<T>,
+    halo_current: bool,
+    steps: usize,
+    output_steps: usize,
+    steps_done: usize,
+    output_path: PathBuf,
+    outputs: usize,
+    dt: f64,
+    seed: u64,
+    centring: Centring,
+}
+
+impl<T, F, C> DistributedSimulation<T, F, C>
+where
+    T: Clone + Send + Sync + Encode + VtkOutput,
+    F: Operator<T> + Clone,
+    C: Transport,
+{
+    /// Sets up the block of this process, without communicating yet
+    ///
+    /// # Errors
+    ///
+    /// If the domain can not be split into one block per process, or if the parameters contain settings
+    /// which are not supported by a distributed simulation, an error will be returned.
+    pub fn new<H>(params: IslParams<T, F, H>, transport: C) -> Result<Self, IslError>
+    where
+        H: Fn(usize, usize) -> T,
+    {
+        let IslParams {
+            dimension,
+            op,
+            height,
+            steps,
+            output_steps,
+            neighbours,
+            output_path,
+            controller,
+            executor,
+            update_order,
+            dt,
+            adaptive_dt,
+            mask,
+            forcing,
+            decomposition,
+            seed,
+            centring,
+            integrator,
+            ..
+        } = params;
+
+        let unsupported = if controller.is_some() {
+            Some("a distributed simulation can not be paused by a controller")
+        } else if executor.is_some() {
+            Some("a distributed simulation computes every block on a single thread, it takes no executor")
+        } else if update_order.colours() != 1 {
+            Some("a distributed simulation only supports the Jacobi update order")
+        } else if integrator.is_some() {
+            Some("a distributed simulation can not be combined with an integrator")
+        } else if adaptive_dt.is_some() {
+            Some("a distributed simulation can not be combined with an adaptive dt")
+        } else if mask.is_some() {
+            Some("a distributed simulation can not be combined with a mask")
+        } else if forcing.is_some() {
+            Some("a distributed simulation can not be combined with a forcing")
+        } else {
+            None
+        };
+        if let Some(message) = unsupported {
+            return Err(IslError::InvalidParams(message));
+        }
+        if op.offsets().is_some_and(|offsets| offsets != neighbours) {
+            return Err(IslError::InvalidParams(
+                "the neighbours of IslParams must match the offsets of the operator",
+            ));
+        }
+        op.check(dimension)?;
+
+        let regions = Region::decompose(dimension, transport.size(), decomposition)?;
+        let halo = neighbours.iter().fold((1, 1), |(x, y), offset| {
+            (
+                x.max(offset.0.unsigned_abs() as usize),
+                y.max(offset.1.unsigned_abs() as usize),
+            )
+        });
+        let local = extend(&regions[transport.rank()], halo, dimension);
+
+        let mut values = Vec::with_capacity(local.len());
+        for y in local.y.clone() {
+            for x in local.x.clone() {
+                values.push(height(x, y));
+            }
+        }
+
+        Ok(Self {
+            transport,
+            op,
+            neighbours,
+            dimension,
+            regions,
+            halo,
+            local,
+            values,
+            halo_current: false,
+            steps,
+            output_steps,
+            steps_done: 0,
+            output_path,
+            outputs: 0,
+            dt,
+            seed,
+            centring,
+        })
+    }
+
+    /// Rank of this process
+    pub fn rank(&self) -> usize {
+        self.transport.rank()
+    }
+
+    /// The block owned by this process
+    pub fn region(&self) -> &Region {
+        &self.regions[self.rank()]
+    }
+
+    /// Number of steps calculated so far
+    pub fn steps_done(&self) -> usize {
+        self.steps_done
+    }
+
+    /// Simulated time so far, the number of steps times `dt`
+    pub fn time(&self) -> f64 {
+        self.steps_done as f64 * self.dt
+    }
+
+    /// The value of the cell (x,y), which must lie in the block of this process
+    pub fn get(&self, x: usize, y: usize) -> T {
+        assert!(
+            self.region().contains(x, y),
+            "({x},{y}) is not known to rank {}",
+            self.rank()
+        );
+        self.values[self.index(x, y)].clone()
+    }
+
+    /// Advances the simulation by one step, all processes have to call this together
+    pub fn step(&mut self) -> io::Result<()> {
+        if !self.halo_current {
+            self.exchange()?;
+        }
+
+        let own = self.region().clone();
+        let context = Context::new(self.steps_done, self.time(), self.dt).seeded(self.seed);
+        let mut next = Vec::with_capacity(own.len());
+        for y in own.y.clone() {
+            for x in own.x.clone() {
+                let neighbours = self
+                    .neighbours
+                    .iter()
+                    .map(|offset| {
+                        let x = x.checked_add_signed(offset.0 as isize)?;
+                        let y = y.checked_add_signed(offset.1 as isize)?;
+                        (x < self.dimension.0 && y < self.dimension.1)
+                            .then(|| &self.values[self.index(x, y)])
+                    })
+                    .collect();
+                let value = &self.values[self.index(x, y)];
+                next.push(self.op.apply(&context.at(x, y), value, neighbours));
+            }
+        }
+
+        let mut next = next.into_iter();
+        for y in own.y.clone() {
+            for x in own.x.clone() {
+                let index = self.index(x, y);
+                self.values[index] = next.next().unwrap();
+            }
+        }
+
+        self.steps_done += 1;
+        self.halo_current = false;
+        Ok(())
+    }
+
+    /// Advances the simulation by `n` steps
+    pub fn step_n(&mut self, n: usize) -> io::Result<()> {
+        for _ in 0..n {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the block of this process into the next output files, all processes have to call this together
+    pub fn write_output(&mut self) -> io::Result<()> {
+        // the pieces of point data share their upper border with the next one, which lies in the halo
+        if self.centring == Centring::Point && !self.halo_current {
+            self.exchange()?;
+        }
+
+        let idx = self.outputs;
+        let piece = self.piece(self.rank());
+        let mut arrays = vtk_writer::arrays::<T>(piece.len());
+        for y in piece.y.clone() {
+            for x in piece.x.clone() {
+                vtk_writer::push_values(&mut arrays, Some(&self.values[self.index(x, y)]));
+            }
+        }
+        let columns: Vec<_> = arrays
+            .iter()
+            .map(|array| (array.name.clone(), array.type_name(), array.num_comp()))
+            .collect();
+        vtk_writer::write_piece(
+            &self.output_path,
+            idx,
+            self.rank(),
+            &piece,
+            arrays,
+            self.centring,
+        );
+
+        self.outputs += 1;
+        if self.rank() == 0 {
+            let pieces: Vec<Region> = (0..self.regions.len())
+                .map(|rank| self.piece(rank))
+                .collect();
+            vtk_writer::write_parallel(
+                &self.output_path,
+                idx,
+                self.dimension,
+                &pieces,
+                &columns,
+                self.centring,
+            );
+            vtk_writer::write_collection(&self.output_path, idx, self.time(), "pvts");
+        }
+        Ok(())
+    }
+
+    /// Runs the number of steps given in [IslParams], writing output files as configured there
+    pub fn run(&mut self) -> io::Result<()> {
+        let every_n_steps = self.steps.checked_div(self.output_steps).unwrap_or(0);
+
+        for step in 1..=self.steps {
+            self.step()?;
+            if step.is_multiple_of(every_n_steps) {
+                self.write_output()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the cells other ranks read to them and receives the halo of this rank
+    fn exchange(&mut self) -> io::Result<()> {
+        let rank = self.rank();
+        for other in 0..self.regions.len() {
+            if other == rank {
+                continue;
+            }
+            let other_local = extend(&self.regions[other], self.halo, self.dimension);
+            let outgoing = intersection(&other_local, &self.regions[rank]);
+            let incoming = intersection(&self.local, &self.regions[other]);
+
+            // the lower rank sends first, so no pair of ranks waits for each other
+            if rank < other {
+                self.send(other, outgoing)?;
+                self.receive(other, incoming)?;
+            } else {
+                self.receive(other, incoming)?;
+                self.send(other, outgoing)?;
+            }
+        }
+        self.halo_current = true;
+        Ok(())
+    }
+
+    fn send(&mut self, to: usize, region: Option<Region>) -> io::Result<()> {
+        let Some(region) = region else {
+            return Ok(());
+        };
+        let mut message = vec![];
+        for y in region.y.clone() {
+            for x in region.x.clone() {
+                self.values[self.index(x, y)].encode(&mut message);
+            }
+        }
+        self.transport.send(to, &message)
+    }
+
+    fn receive(&mut self, from: usize, region: Option<Region>) -> io::Result<()> {
+        let Some(region) = region else {
+            return Ok(());
+        };
+        let message = self.transport.receive(from)?;
+        let mut bytes = message.as_slice();
+        let mut received = Vec::with_capacity(region.len());
+        for _ in 0..region.len() {
+            received.push(T::decode(&mut bytes)?);
+        }
+        if !bytes.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "the halo from rank {from} holds more than the {} cells of {region:?}",
+                    region.len()
+                ),
+            ));
+        }
+
+        let mut received = received.into_iter();
+        for y in region.y.clone() {
+            for x in region.x.clone() {
+                let index = self.index(x, y);
+                self.values[index] = received.next().unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    /// The block of the given rank, extended by the first row and column of the next blocks for point data.
+    /// Cells don't overlap, so cell data needs no extension.
+    fn piece(&self, rank: usize) -> Region {
+        let region = &self.regions[rank];
+        if self.centring == Centring::Cell {
+            return region.clone();
+        }
+        Region {
+            x: region.x.start..(region.x.end + 1).min(self.dimension.0),
+            y: region.y.start..(region.y.end + 1).min(self.dimension.1),
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        (y - self.local.y.start) * self.local.x.len() + (x - self.local.x.start)
+    }
+}
+
+/// The region grown by `halo` cells in every direction, without leaving the domain
+fn extend(region: &Region, halo: (usize, usize), dimension: (usize, usize)) -> Region {
+    Region {
+        x: region.x.start.saturating_sub(halo.0)..(region.x.end + halo.0).min(dimension.0),
+        y: region.y.start.saturating_sub(halo.1)..(region.y.end + halo.1).min(dimension.1),
+    }
+}
+
+fn intersection(a: &Region, b: &Region) -> Option<Region> {
+    let region = Region {
+        x: a.x.start.max(b.x.start)..a.x.end.min(b.x.end),
+        y: a.y.start.max(b.y.start)..a.y.end.min(b.y.end),
+    };
+    (!region.x.is_empty() && !region.y.is_empty()).then_some(region)
+}
@@ -0,0 +1,109 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::{
+    grid::InvalidThreadNumber,
+    pool::WorkerPool,
+    region::{Decomposition, Region},
+};
+
+/// A unit of work, called with the index of the block it should process
+pub type Job = Arc<dyn Fn(usize) + Send + Sync>;
+
+/// Strategy for running the blocks of a simulation.
+///
+/// Every step consists of two phases. First each block computes the next values of its cells,
+/// then each block commits them. An executor runs a phase by calling the job for every block
+/// and must only return once all calls have finished, which acts as the barrier between phases.
+///
+/// rs_isl ships with [SerialExecutor], [ThreadExecutor] and, behind the `rayon` feature, `RayonExecutor`.
+/// Select one with [IslParams::with_executor](crate::IslParams::with_executor).
+pub trait Executor: Send {
+    /// Splits the domain of the given size, (x,y), into the blocks which are passed to [Executor::run]
+    fn partition(&self, dimension: (usize, usize)) -> Result<Vec<Region>, InvalidThreadNumber>;
+
+    /// Like [Executor::partition], but the blocks should carry about the same total cost, given per cell (x,y).
+    ///
+    /// Executors which balance their load otherwise, e.g. by scheduling many small blocks, may ignore the cost,
+    /// which is the default.
+    fn partition_by_cost(
+        &self,
+        dimension: (usize, usize),
+        cost: &dyn Fn(usize, usize) -> f64,
+    ) -> Result<Vec<Region>, InvalidThreadNumber> {
+        let _ = cost;
+        self.partition(dimension)
+    }
+
+    /// Calls `job` for every block in `0..blocks` and waits for all calls to finish
+    fn run(&self, blocks: usize, job: Job);
+}
+
+/// Runs every block on the calling thread, one after the other.
+///
+/// Needs no synchronisation between threads and always processes the cells in the same order.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SerialExecutor;
+
+impl Executor for SerialExecutor {
+    fn partition(&self, dimension: (usize, usize)) -> Result<Vec<Region>, InvalidThreadNumber> {
+        Ok(vec![Region::whole(dimension)])
+    }
+
+    fn run(&self, blocks: usize, job: Job) {
+        for block in 0..blocks {
+            job(block);
+        }
+    }
+}
+
+/// Runs every block on its own thread.
+///
+/// The domain is split into one block per runner, arranged as close to a square as possible unless
+/// another [Decomposition] is chosen.
+/// The threads are started with the first step, stay alive between steps and are synchronised with barriers.
+/// This is the default executor, using the number of runners given to [IslParams::new](crate::IslParams::new).
+pub struct ThreadExecutor {
+    runners: usize,
+    decomposition: Decomposition,
+    pool: OnceLock<WorkerPool>,
+}
+
+impl ThreadExecutor {
+    pub fn new(runners: usize) -> Self {
+        Self {
+            runners,
+            decomposition: Decomposition::default(),
+            pool: OnceLock::new(),
+        }
+    }
+
+    /// Arrange the blocks as given, instead of as close to a square as possible
+    pub fn with_decomposition(mut self, decomposition: Decomposition) -> Self {
+        self.decomposition = decomposition;
+        self
+    }
+}
+
+impl Executor for ThreadExecutor {
+    fn partition(&self, dimension: (usize, usize)) -> Result<Vec<Region>, InvalidThreadNumber> {
+        Region::decompose(dimension, self.runners, self.decomposition)
+    }
+
+    fn partition_by_cost(
+        &self,
+        dimension: (usize, usize),
+        cost: &dyn Fn(usize, usize) -> f64,
+    ) -> Result<Vec<Region>, InvalidThreadNumber> {
+        Region::decompose_balanced(dimension, self.runners, self.decomposition, cost)
+    }
+
+    fn run(&self, blocks: usize, job: Job) {
+        assert_eq!(
+            blocks, self.runners,
+            "a ThreadExecutor runs exactly one block per runner"
+        );
+        self.pool
+            .get_or_init(|| WorkerPool::new(self.runners))
+            .run(job);
+    }
+}
@@ -0,0 +1,670 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// The weight of the cell at an offset
+type Weight = ((i8, i8), f64);
+
+/// A declarative description of the next value of a cell, which rs_isl can inspect, simplify and print.
+///
+/// Expressions are built from constants, the values of neighbouring cells, the values of other fields of a
+/// [MultiField](crate::MultiField) and the position and time of the cell, combined with arithmetic operators
+/// and a few functions. They are evaluated in `f64` and run as operators through a [Kernel](crate::Kernel).
+///
+/// Neighbours outside of the domain read as zero, like in a [LinearStencil](crate::LinearStencil).
+/// [Expr::exists] tells whether a neighbour is there, e.g. for boundaries without flux.
+///
+/// Expressions are printed in a small infix syntax, which [str::parse] reads back:
+///
+/// | Syntax | Meaning |
+/// |---|---|
+/// | `u[-1,0]` | the cell at the offset (-1,0) |
+/// | `has[-1,0]` | 1 if that cell exists, otherwise 0 |
+/// | `$v[0,1]` | the field named `v` at the offset (0,1) |
+/// | `x`, `y`, `step`, `t`, `dt` | the values of the [Context](crate::Context) |
+/// | `+ - * /`, `-(...)` | arithmetic |
+/// | `abs sqrt exp sin cos min max` | functions, `min` and `max` take two arguments |
+///
+/// # Example
+/// ```rust
+/// use rs_isl::Expr;
+///
+/// let alpha = 0.2;
+/// let laplace = Expr::cell(-1, 0) + Expr::cell(1, 0) + Expr::cell(0, -1) + Expr::cell(0, 1)
+///     - 4.0 * Expr::cell(0, 0);
+/// let heat = Expr::cell(0, 0) + alpha * laplace;
+///
+/// let printed = heat.to_string();
+/// assert_eq!(printed, "u[0,0] + 0.2 * (u[-1,0] + u[1,0] + u[0,-1] + u[0,1] - 4.0 * u[0,0])");
+/// assert_eq!(printed.parse::<Expr>().unwrap(), heat);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Const(f64),
+    /// The value of the cell at the offset, (0,0) is the cell itself
+    Cell((i8, i8)),
+    /// 1 if the neighbour at the offset lies inside of the domain, otherwise 0
+    Exists((i8, i8)),
+    /// The value of the named field at the offset
+    Field(String, (i8, i8)),
+    Var(Var),
+    Unary(Unary, Box<Expr>),
+    Binary(Binary, Box<Expr>, Box<Expr>),
+}
+
+/// The values of the [Context](crate::Context) an [Expr] can read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Var {
+    X,
+    Y,
+    Step,
+    Time,
+    Dt,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unary {
+    Neg,
+    Abs,
+    Sqrt,
+    Exp,
+    Sin,
+    Cos,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Min,
+    Max,
+}
+
+impl Unary {
+    pub fn apply(self, a: f64) -> f64 {
+        match self {
+            Unary::Neg => -a,
+            Unary::Abs => a.abs(),
+            Unary::Sqrt => a.sqrt(),
+            Unary::Exp => a.exp(),
+            Unary::Sin => a.sin(),
+            Unary::Cos => a.cos(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Unary::Neg => "-",
+            Unary::Abs => "abs",
+            Unary::Sqrt => "sqrt",
+            Unary::Exp => "exp",
+            Unary::Sin => "sin",
+            Unary::Cos => "cos",
+        }
+    }
+}
+
+impl Binary {
+    pub fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Binary::Add => a + b,
+            Binary::Sub => a - b,
+            Binary::Mul => a * b,
+            Binary::Div => a / b,
+            Binary::Min => a.min(b),
+            Binary::Max => a.max(b),
+        }
+    }
+}
+
+impl Expr {
+    pub fn constant(value: f64) -> Self {
+        Expr::Const(value)
+    }
+
+    /// The value of the cell at the offset (x,y), zero outside of the domain
+    pub fn cell(x: i8, y: i8) -> Self {
+        Expr::Cell((x, y))
+    }
+
+    /// 1 if the cell at the offset (x,y) lies inside of the domain, otherwise 0
+    pub fn exists(x: i8, y: i8) -> Self {
+        Expr::Exists((x, y))
+    }
+
+    /// The value of the named field of a [MultiField](crate::MultiField) at the offset (x,y), zero outside of the domain
+    pub fn field(name: &str, x: i8, y: i8) -> Self {
+        Expr::Field(name.to_string(), (x, y))
+    }
+
+    pub fn x() -> Self {
+        Expr::Var(Var::X)
+    }
+
+    pub fn y() -> Self {
+        Expr::Var(Var::Y)
+    }
+
+    pub fn step() -> Self {
+        Expr::Var(Var::Step)
+    }
+
+    pub fn time() -> Self {
+        Expr::Var(Var::Time)
+    }
+
+    pub fn dt() -> Self {
+        Expr::Var(Var::Dt)
+    }
+
+    pub fn abs(self) -> Self {
+        Expr::Unary(Unary::Abs, Box::new(self))
+    }
+
+    pub fn sqrt(self) -> Self {
+        Expr::Unary(Unary::Sqrt, Box::new(self))
+    }
+
+    pub fn exp(self) -> Self {
+        Expr::Unary(Unary::Exp, Box::new(self))
+    }
+
+    pub fn sin(self) -> Self {
+        Expr::Unary(Unary::Sin, Box::new(self))
+    }
+
+    pub fn cos(self) -> Self {
+        Expr::Unary(Unary::Cos, Box::new(self))
+    }
+
+    pub fn min(self, other: impl Into<Expr>) -> Self {
+        Expr::Binary(Binary::Min, Box::new(self), Box::new(other.into()))
+    }
+
+    pub fn max(self, other: impl Into<Expr>) -> Self {
+        Expr::Binary(Binary::Max, Box::new(self), Box::new(other.into()))
+    }
+
+    /// The offsets of the neighbours the expression reads, in the order they first appear, without (0,0)
+    pub fn offsets(&self) -> Vec<(i8, i8)> {
+        let mut offsets = vec![];
+        self.visit(&mut |expr| {
+            if let Expr::Cell(offset) | Expr::Exists(offset) = expr {
+                if *offset != (0, 0) && !offsets.contains(offset) {
+                    offsets.push(*offset);
+                }
+            }
+        });
+        offsets
+    }
+
+    /// The names of the fields the expression reads, in the order they first appear
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = vec![];
+        self.visit(&mut |expr| {
+            if let Expr::Field(name, _) = expr {
+                if !fields.contains(&name.as_str()) {
+                    fields.push(name.as_str());
+                }
+            }
+        });
+        fields
+    }
+
+    /// Simplifies the expression by evaluating constant parts and dropping additions of zero,
+    /// multiplications by one and double negations
+    pub fn fold(&self) -> Expr {
+        match self {
+            Expr::Unary(op, a) => match (op, a.fold()) {
+                (_, Expr::Const(a)) => Expr::Const(op.apply(a)),
+                (Unary::Neg, Expr::Unary(Unary::Neg, a)) => *a,
+                (_, a) => Expr::Unary(*op, Box::new(a)),
+            },
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.fold(), b.fold());
+                if let (Expr::Const(a), Expr::Const(b)) = (&a, &b) {
+                    return Expr::Const(op.apply(*a, *b));
+                }
+                let is = |expr: &Expr, value: f64| *expr == Expr::Const(value);
+                match op {
+                    Binary::Add if is(&a, 0.0) => b,
+                    Binary::Add | Binary::Sub if is(&b, 0.0) => a,
+                    Binary::Mul if is(&a, 1.0) => b,
+                    Binary::Mul | Binary::Div if is(&b, 1.0) => a,
+                    _ => Expr::Binary(*op, Box::new(a), Box::new(b)),
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// The expression as seen from the cell at `offset`, all offsets and positions are moved by it
+    pub fn shifted(&self, offset: (i8, i8)) -> Expr {
+        let shift = |(x, y): (i8, i8)| {
+            (
+                x.checked_add(offset.0)
+                    .expect("shifted offset out of range"),
+                y.checked_add(offset.1)
+                    .expect("shifted offset out of range"),
+            )
+        };
+        match self {
+            Expr::Cell(at) => Expr::Cell(shift(*at)),
+            Expr::Exists(at) => Expr::Exists(shift(*at)),
+            Expr::Field(name, at) => Expr::Field(name.clone(), shift(*at)),
+            Expr::Var(Var::X) if offset.0 != 0 => Expr::x() + offset.0 as f64,
+            Expr::Var(Var::Y) if offset.1 != 0 => Expr::y() + offset.1 as f64,
+            Expr::Unary(op, a) => Expr::Unary(*op, Box::new(a.shifted(offset))),
+            Expr::Binary(op, a, b) => Expr::Binary(
+                *op,
+                Box::new(a.shifted(offset)),
+                Box::new(b.shifted(offset)),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// Fuses the computation of a field into this expression.
+    ///
+    /// Every read of the named field is replaced by `definition`, an expression in the cells of the field
+    /// it is computed from, shifted to the offset of the read. This avoids storing intermediate fields,
+    /// e.g. a Laplacian, at the cost of computing them once per read.
+    pub fn inline(&self, name: &str, definition: &Expr) -> Expr {
+        match self {
+            Expr::Field(field, offset) if field == name => definition.shifted(*offset),
+            Expr::Unary(op, a) => Expr::Unary(*op, Box::new(a.inline(name, definition))),
+            Expr::Binary(op, a, b) => Expr::Binary(
+                *op,
+                Box::new(a.inline(name, definition)),
+                Box::new(b.inline(name, definition)),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// The weights of the cells and the constant, if the expression is a weighted sum of cells.
+    ///
+    /// Cells at the same offset are combined into one weight.
+    pub fn linear_terms(&self) -> Option<(Vec<Weight>, f64)> {
+        let scale = |(terms, constant): (Vec<Weight>, f64), factor: f64| {
+            let terms = terms
+                .into_iter()
+                .map(|(offset, weight)| (offset, weight * factor))
+                .collect();
+            (terms, constant * factor)
+        };
+        let constant = |expr: &Expr| match expr.linear_terms()? {
+            (terms, constant) if terms.is_empty() => Some(constant),
+            _ => None,
+        };
+
+        match self {
+            Expr::Const(value) => Some((vec![], *value)),
+            Expr::Cell(offset) => Some((vec![(*offset, 1.0)], 0.0)),
+            Expr::Unary(Unary::Neg, a) => Some(scale(a.linear_terms()?, -1.0)),
+            Expr::Binary(op @ (Binary::Add | Binary::Sub), a, b) => {
+                let (mut terms, mut sum) = a.linear_terms()?;
+                let factor = if *op == Binary::Add { 1.0 } else { -1.0 };
+                let (other, constant) = scale(b.linear_terms()?, factor);
+                for (offset, weight) in other {
+                    match terms.iter_mut().find(|(at, _)| *at == offset) {
+                        Some((_, sum)) => *sum += weight,
+                        None => terms.push((offset, weight)),
+                    }
+                }
+                sum += constant;
+                Some((terms, sum))
+            }
+            Expr::Binary(Binary::Mul, a, b) => match (constant(a), constant(b)) {
+                (Some(factor), _) => Some(scale(b.linear_terms()?, factor)),
+                (_, Some(factor)) => Some(scale(a.linear_terms()?, factor)),
+                _ => None,
+            },
+            Expr::Binary(Binary::Div, a, b) => Some(scale(a.linear_terms()?, 1.0 / constant(b)?)),
+            _ => None,
+        }
+    }
+
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Expr)) {
+        f(self);
+        match self {
+            Expr::Unary(_, a) => a.visit(f),
+            Expr::Binary(_, a, b) => {
+                a.visit(f);
+                b.visit(f);
+            }
+            _ => {}
+        }
+    }
+
+    /// Binding strength of the outermost operator, atoms bind strongest
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary(Binary::Add | Binary::Sub, ..) => 1,
+            Expr::Binary(Binary::Mul | Binary::Div, ..) => 2,
+            Expr::Const(value) if value.is_sign_negative() => 0,
+            _ => 3,
+        }
+    }
+
+    /// Writes the expression, in parentheses if it binds weaker than `precedence`
+    fn write(&self, f: &mut fmt::Formatter, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "(")?;
+            self.write(f, 0)?;
+            return write!(f, ")");
+        }
+
+        match self {
+            Expr::Const(value) => write!(f, "{value:?}"),
+            Expr::Cell((x, y)) => write!(f, "u[{x},{y}]"),
+            Expr::Exists((x, y)) => write!(f, "has[{x},{y}]"),
+            Expr::Field(name, (x, y)) => write!(f, "${name}[{x},{y}]"),
+            Expr::Var(var) => write!(
+                f,
+                "{}",
+                match var {
+                    Var::X => "x",
+                    Var::Y => "y",
+                    Var::Step => "step",
+                    Var::Time => "t",
+                    Var::Dt => "dt",
+                }
+            ),
+            Expr::Unary(op, a) => {
+                write!(f, "{}(", op.name())?;
+                a.write(f, 0)?;
+                write!(f, ")")
+            }
+            Expr::Binary(op @ (Binary::Min | Binary::Max), a, b) => {
+                write!(f, "{}(", if *op == Binary::Min { "min" } else { "max" })?;
+                a.write(f, 0)?;
+                write!(f, ", ")?;
+                b.write(f, 0)?;
+                write!(f, ")")
+            }
+            Expr::Binary(op, a, b) => {
+                let (symbol, precedence) = match op {
+                    Binary::Add => ("+", 1),
+                    Binary::Sub => ("-", 1),
+                    Binary::Mul => ("*", 2),
+                    _ => ("/", 2),
+                };
+                // operators are left associative, so the right side needs parentheses at the same precedence
+                a.write(f, precedence)?;
+                write!(f, " {symbol} ")?;
+                b.write(f, precedence + 1)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl From<f64> for Expr {
+    fn from(value: f64) -> Self {
+        Expr::Const(value)
+    }
+}
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        Expr::Unary(Unary::Neg, Box::new(self))
+    }
+}
+
+macro_rules! impl_binary {
+    ($($trait:ident, $method:ident, $op:ident;)*) => {
+        $(
+            impl<R: Into<Expr>> $trait<R> for Expr {
+                type Output = Expr;
+
+                fn $method(self, other: R) -> Expr {
+                    Expr::Binary(Binary::$op, Box::new(self), Box::new(other.into()))
+                }
+            }
+
+            impl $trait<Expr> for f64 {
+                type Output = Expr;
+
+                fn $method(self, other: Expr) -> Expr {
+                    Expr::Binary(Binary::$op, Box::new(Expr::Const(self)), Box::new(other))
+                }
+            }
+        )*
+    };
+}
+
+impl_binary! {
+    Add, add, Add;
+    Sub, sub, Sub;
+    Mul, mul, Mul;
+    Div, div, Div;
+}
+
+/// The error returned when parsing an [Expr] fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseExprError {
+    /// Byte position in the input at which parsing failed
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ParseExprError {}
+
+impl FromStr for Expr {
+    type Err = ParseExprError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input, position: 0 };
+        let expr = parser.sum()?;
+        parser.skip_whitespace();
+        if parser.position < input.len() {
+            return Err(parser.error("unexpected input"));
+        }
+        Ok(expr)
+    }
+}
+
+/// Recursive descent parser for the syntax printed by [Expr]
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ParseExprError {
+        ParseExprError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `token` if the input continues with it
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseExprError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{token}`")))
+        }
+    }
+
+    /// Consumes the longest prefix of characters matching `accept`
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &str {
+        self.skip_whitespace();
+        let start = self.position;
+        let len = self
+            .rest()
+            .find(|c: char| !accept(c))
+            .unwrap_or(self.rest().len());
+        self.position += len;
+        &self.input[start..self.position]
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseExprError> {
+        let mut expr = self.product()?;
+        loop {
+            if self.eat("+") {
+                expr = expr + self.product()?;
+            } else if self.eat("-") {
+                expr = expr - self.product()?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, ParseExprError> {
+        let mut expr = self.unary()?;
+        loop {
+            if self.eat("*") {
+                expr = expr * self.unary()?;
+            } else if self.eat("/") {
+                expr = expr / self.unary()?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseExprError> {
+        if !self.eat("-") {
+            return self.atom();
+        }
+        if self.eat("(") {
+            let expr = self.sum()?;
+            self.expect(")")?;
+            return Ok(-expr);
+        }
+        // a negative number
+        match self.atom()? {
+            Expr::Const(value) => Ok(Expr::Const(-value)),
+            _ => Err(self.error("expected a number or `(` after `-`")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i8, ParseExprError> {
+        let number = self.take_while(|c| c == '-' || c.is_ascii_digit());
+        number.parse().map_err(|_| self.error("expected an offset"))
+    }
+
+    /// Reads the rest of an offset after the opening bracket, `x,y]`
+    fn offset(&mut self) -> Result<(i8, i8), ParseExprError> {
+        let x = self.integer()?;
+        self.expect(",")?;
+        let y = self.integer()?;
+        self.expect("]")?;
+        Ok((x, y))
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseExprError> {
+        if self.eat("(") {
+            let expr = self.sum()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        if self.eat("$") {
+            let name = self
+                .take_while(|c| c.is_alphanumeric() || c == '_' || c == '.')
+                .to_string();
+            self.expect("[")?;
+            return Ok(Expr::Field(name, self.offset()?));
+        }
+
+        self.skip_whitespace();
+        if self.rest().starts_with(|c: char| c.is_ascii_digit()) {
+            let number = self.take_while(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E'));
+            let mut number = number.to_string();
+            // the sign of an exponent
+            if number.ends_with(['e', 'E']) && self.rest().starts_with(['-', '+']) {
+                number.push_str(&self.rest()[..1]);
+                self.position += 1;
+                number.push_str(self.take_while(|c| c.is_ascii_digit()));
+            }
+            return number
+                .parse()
+                .map(Expr::Const)
+                .map_err(|_| self.error("invalid number"));
+        }
+
+        let name = self.take_while(|c| c.is_ascii_alphanumeric()).to_string();
+        let unary = match name.as_str() {
+            "abs" => Some(Unary::Abs),
+            "sqrt" => Some(Unary::Sqrt),
+            "exp" => Some(Unary::Exp),
+            "sin" => Some(Unary::Sin),
+            "cos" => Some(Unary::Cos),
+            _ => None,
+        };
+        if let Some(op) = unary {
+            self.expect("(")?;
+            let a = self.sum()?;
+            self.expect(")")?;
+            return Ok(Expr::Unary(op, Box::new(a)));
+        }
+
+        match name.as_str() {
+            "u" | "has" => {
+                self.expect("[")?;
+                let offset = self.offset()?;
+                Ok(if name == "u" {
+                    Expr::Cell(offset)
+                } else {
+                    Expr::Exists(offset)
+                })
+            }
+            "min" | "max" => {
+                self.expect("(")?;
+                let a = self.sum()?;
+                self.expect(",")?;
+                let b = self.sum()?;
+                self.expect(")")?;
+                Ok(if name == "min" { a.min(b) } else { a.max(b) })
+            }
+            "x" => Ok(Expr::x()),
+            "y" => Ok(Expr::y()),
+            "step" => Ok(Expr::step()),
+            "t" => Ok(Expr::time()),
+            "dt" => Ok(Expr::dt()),
+            "inf" => Ok(Expr::Const(f64::INFINITY)),
+            "NaN" => Ok(Expr::Const(f64::NAN)),
+            _ => Err(self.error("expected an expression")),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use std::{any::Any, mem::swap, path::PathBuf, sync::Arc};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    controller::SharedGrid,
+    executor::Executor,
+    grid::{build_blocks, Block, InvalidThreadNumber, IslError},
+    operator::{Context, Operator},
+    order::UpdateOrder,
+    region::Region,
+    view::{GridView, GridViewMut},
+    vtk_writer::{self, Array, Centring},
+    VtkOutput,
+};
+
+/// Handle to one field of a [MultiField].
+///
+/// Handles are cheap to clone. Move a clone into the operator of another field to read this field's values.
+pub struct Field<T> {
+    index: usize,
+    name: Arc<str>,
+    grid: SharedGrid<T>,
+}
+
+impl<T> Clone for Field<T> {
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index,
+            name: self.name.clone(),
+            grid: self.grid.clone(),
+        }
+    }
+}
+
+impl<T> Field<T> {
+    /// The name this field is exported under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value at the position of `context` shifted by `offset`, `None` outside of the domain.
+    ///
+    /// While a step is calculated, this is the value of the previous step, for every field alike.
+    pub fn get(&self, context: &Context, offset: (i8, i8)) -> Option<T>
+    where
+        T: Clone,
+    {
+        let x = context.x.checked_add_signed(offset.0 as isize)?;
+        let y = context.y.checked_add_signed(offset.1 as isize)?;
+        Some(self.grid.get(y)?.get(x)?.read().clone())
+    }
+
+    /// Read access to the current values
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView::new(&self.grid)
+    }
+}
+
+/// The type-erased part of a field, which the runners work with
+trait AnyField: Send + Sync {
+    fn name(&self) -> &str;
+    fn compute(&self, block: usize, context: Context);
+    fn commit(&self, block: usize);
+    fn arrays(&self) -> Vec<Array>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct FieldState<T> {
+    name: Arc<str>,
+    grid: SharedGrid<T>,
+    neighbours: Vec<(i8, i8)>,
+    blocks: Vec<Mutex<Block<T>>>,
+    op: RwLock<Option<Arc<dyn Operator<T>>>>,
+}
+
+impl<T> AnyField for FieldState<T>
+where
+    T: Clone + Send + Sync + VtkOutput + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn compute(&self, block: usize, context: Context) {
+        // fields without an operator keep their values
+        let Some(op) = self.op.read().clone() else {
+            return;
+        };
+        let block = &mut *self.blocks[block].lock();
+        match op.apply_region(&GridView::new(&self.grid), &block.region) {
+            Some(values) => {
+                for (value, cell) in values.into_iter().zip(block.cells.iter_mut()) {
+                    cell.next_val = value;
+                }
+            }
+            None => {
+                for cell in block.cells.iter_mut() {
+                    cell.run(&*op, context);
+                }
+            }
+        }
+    }
+
+    fn commit(&self, block: usize) {
+        if self.op.read().is_none() {
+            return;
+        }
+        for cell in self.blocks[block].lock().cells.iter_mut() {
+            cell.write();
+        }
+    }
+
+    fn arrays(&self) -> Vec<Array> {
+        let mut arrays = vtk_writer::arrays::<T>(self.grid.iter().map(Vec::len).sum());
+        for row in self.grid.iter() {
+            for value in row {
+                vtk_writer::push_values(&mut arrays, Some(&*value.read()));
+            }
+        }
+
+        if arrays.len() == 1 {
+            arrays[0].name = self.name.to_string();
+        } else {
+            for array in arrays.iter_mut() {
+                array.name = format!("{}.{}", self.name, array.name);
+            }
+        }
+        arrays
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A simulation of several coupled fields on the same domain.
+///
+/// Every field has its own type, neighbours and operator. All fields are advanced together:
+/// first each field computes its next values, reading its own neighbours and, through [Field] handles,
+/// the previous values of any other field. Then all fields commit.
+/// Each field is written to the output files under its own name.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// let mut sim = MultiField::new((100, 100), ThreadExecutor::new(4), PathBuf::from("raw")).unwrap();
+///
+/// // a static source term and a field diffusing on the von Neumann neighbourhood
+/// let source = sim.add_field("source", |x, y| if x == 50 && y == 50 { 1.0f32 } else { 0.0 }, vec![]);
+/// let heat = sim.add_field("heat", |_, _| 0.0f32, vec![(-1, 0), (1, 0), (0, -1), (0, 1)]);
+///
+/// let s = source.clone();
+/// sim.set_operator(
+///     &heat,
+///     Contextual(move |context: &Context, num: &f32, nb: Vec<Option<&f32>>| {
+///         let sum: f32 = nb.iter().flatten().map(|v| **v - *num).sum();
+///         num + 0.2 * sum + s.get(context, (0, 0)).unwrap()
+///     }),
+/// )
+/// .unwrap();
+///
+/// sim.run(100, 10);
+/// ```
+pub struct MultiField {
+    dimension: (usize, usize),
+    regions: Vec<Region>,
+    executor: Box<dyn Executor>,
+    fields: Vec<Arc<dyn AnyField>>,
+    steps_done: usize,
+    output_path: PathBuf,
+    outputs: usize,
+    seed: u64,
+    centring: Centring,
+}
+
+impl MultiField {
+    /// Creates a simulation without any fields on a domain of the given size, (x,y)
+    ///
+    /// # Errors
+    ///
+    /// If the executor can not split the domain, e.g. because it has fewer rows than runners.
+    pub fn new(
+        dimension: (usize, usize),
+        executor: impl Executor + 'static,
+        output_path: PathBuf,
+    ) -> Result<Self, InvalidThreadNumber> {
+        let regions = executor.partition(dimension)?;
+        Ok(Self {
+            dimension,
+            regions,
+            executor: Box::new(executor),
+            fields: vec![],
+            steps_done: 0,
+            output_path,
+            outputs: 0,
+            seed: 0,
+            centring: Centring::Point,
+        })
+    }
+
+    /// Adds a field, initialised from `init` and reading the given neighbours.
+    ///
+    /// The field keeps its initial values until an operator is set with [MultiField::set_operator].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field with the same name already exists.
+    pub fn add_field<T, H>(&mut self, name: &str, init: H, neighbours: Vec<(i8, i8)>) -> Field<T>
+    where
+        T: Clone + Send + Sync + VtkOutput + 'static,
+        H: Fn(usize, usize) -> T,
+    {
+        assert!(
+            self.fields.iter().all(|field| field.name() != name),
+            "a field named {name} already exists"
+        );
+
+        let grid: Vec<Vec<Arc<RwLock<T>>>> = (0..self.dimension.1)
+            .map(|y| {
+                (0..self.dimension.0)
+                    .map(|x| Arc::new(RwLock::new(init(x, y))))
+                    .collect()
+            })
+            .collect();
+
+        let mut swapped = neighbours.clone();
+        swapped.iter_mut().for_each(|(x, y)| swap(x, y));
+        let blocks = build_blocks(
+            &grid,
+            &swapped,
+            self.regions.clone(),
+            None,
+            &UpdateOrder::Jacobi,
+            None,
+        );
+
+        let field = Field {
+            index: self.fields.len(),
+            name: name.into(),
+            grid: Arc::new(grid),
+        };
+        self.fields.push(Arc::new(FieldState {
+            name: field.name.clone(),
+            grid: field.grid.clone(),
+            neighbours,
+            blocks,
+            op: RwLock::new(None),
+        }));
+        field
+    }
+
+    /// Seeds the random numbers of [Context::rng], 0 by default.
+    ///
+    /// All fields see the same numbers for the same cell and step.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Chooses whether the output files hold point data, the default, or cell data, see [Centring]
+    pub fn set_centring(&mut self, centring: Centring) {
+        self.centring = centring;
+    }
+
+    /// Sets the operator calculating the next values of `field`
+    ///
+    /// # Errors
+    ///
+    /// If the operator's offsets differ from the field's neighbours, or the operator can not run on this domain,
+    /// e.g. a [Kernel](crate::Kernel) reading a field which is not bound to it, an error will be returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field belongs to another simulation.
+    pub fn set_operator<T, F>(&mut self, field: &Field<T>, op: F) -> Result<(), IslError>
+    where
+        T: Clone + Send + Sync + VtkOutput + 'static,
+        F: Operator<T> + 'static,
+    {
+        let state = self.state(field);
+        if op
+            .offsets()
+            .is_some_and(|offsets| offsets != state.neighbours)
+        {
+            return Err(IslError::InvalidParams(
+                "the neighbours of the field must match the offsets of the operator",
+            ));
+        }
+        op.check(self.dimension)?;
+
+        *state.op.write() = Some(Arc::new(op));
+        Ok(())
+    }
+
+    /// Read and write access to the current values of `field`, between steps
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field belongs to another simulation.
+    pub fn view_mut<T>(&mut self, field: &Field<T>) -> GridViewMut<'_, T>
+    where
+        T: Clone + Send + Sync + VtkOutput + 'static,
+    {
+        GridViewMut::new(&self.state(field).grid)
+    }
+
+    fn state<T>(&self, field: &Field<T>) -> &FieldState<T>
+    where
+        T: Clone + Send + Sync + VtkOutput + 'static,
+    {
+        self.fields
+            .get(field.index)
+            .and_then(|state| state.as_any().downcast_ref::<FieldState<T>>())
+            .filter(|state| Arc::ptr_eq(&state.grid, &field.grid))
+            .expect("the field does not belong to this simulation")
+    }
+
+    /// Advances all fields by one step
+    pub fn step(&mut self) {
+        let step = self.steps_done;
+        let context = Context::new(step, step as f64, 1.0).seeded(self.seed);
+
+        let fields = self.fields.clone();
+        self.executor.run(
+            self.regions.len(),
+            Arc::new(move |block| {
+                for field in fields.iter() {
+                    field.compute(block, context);
+                }
+            }),
+        );
+
+        let fields = self.fields.clone();
+        self.executor.run(
+            self.regions.len(),
+            Arc::new(move |block| {
+                for field in fields.iter() {
+                    field.commit(block);
+                }
+            }),
+        );
+
+        self.steps_done += 1;
+    }
+
+    /// Advances all fields by `n` steps
+    pub fn step_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Number of steps calculated so far
+    pub fn steps_done(&self) -> usize {
+        self.steps_done
+    }
+
+    /// Writes the current values of all fields into the next output file
+    pub fn write_output(&mut self) {
+        let arrays = self
+            .fields
+            .iter()
+            .flat_map(|field| field.arrays())
+            .collect();
+        let dim = (self.dimension.1 as u32, self.dimension.0 as u32);
+        vtk_writer::write_arrays(&self.output_path, self.outputs, dim, arrays, self.centring);
+        vtk_writer::write_collection(
+            &self.output_path,
+            self.outputs,
+            self.steps_done as f64,
+            self.centring.extension(),
+        );
+        self.outputs += 1;
+    }
+
+    /// Runs `steps` steps, writing `output_steps` output files evenly spread over them
+    pub fn run(&mut self, steps: usize, output_steps: usize) {
+        let every_n_steps = steps.checked_div(output_steps).unwrap_or(0);
+
+        for step in 1..=steps {
+            self.step();
+            if step.is_multiple_of(every_n_steps) {
+                self.write_output();
+            }
+        }
+    }
+}
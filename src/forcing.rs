@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use crate::region::Region;
+
+type Source<T> = Arc<dyn Fn(usize, (usize, usize), &mut T) + Send + Sync>;
+
+/// Source terms which change cells from outside of the operator, e.g. an oscillating point source or a moving heat source.
+///
+/// After every step, each source is called with the number of the step, the position (x,y) and the new value
+/// of every cell in its region. Every runner applies the sources to its own cells, in the order they were added.
+/// Inactive cells of a mask are not forced.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// let forcing = Forcing::new()
+///     // a point source at (50,50), oscillating with a period of 20 steps
+///     .with_source(Region { x: 50..51, y: 50..51 }, |step, _, value: &mut f32| {
+///         *value += (step as f32 * std::f32::consts::TAU / 20.0).sin()
+///     })
+///     // a heat source in the bottom rows, moving one cell to the right every 10 steps
+///     .with_source(Region { x: 0..100, y: 0..2 }, |step, (x, _y), value: &mut f32| {
+///         if x == step / 10 {
+///             *value += 1.0
+///         }
+///     });
+///
+/// let op = |num: &f32, _nb: Vec<Option<&f32>>| *num;
+/// let params = IslParams::new((100, 100), op, 4, |_, _| 0.0, 100, 10, vec![], PathBuf::from("raw"))
+///     .with_forcing(forcing);
+/// run_isl(params).unwrap();
+/// ```
+pub struct Forcing<T> {
+    sources: Vec<(Region, Source<T>)>,
+}
+
+impl<T> Clone for Forcing<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sources: self.sources.clone(),
+        }
+    }
+}
+
+impl<T> Default for Forcing<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forcing<T> {
+    /// Creates a forcing without sources
+    pub fn new() -> Self {
+        Self { sources: vec![] }
+    }
+
+    /// Adds a source, which is applied to all cells (x,y) inside `region` and called as `source(step, (x, y), value)`
+    pub fn with_source(
+        mut self,
+        region: Region,
+        source: impl Fn(usize, (usize, usize), &mut T) + Send + Sync + 'static,
+    ) -> Self {
+        self.sources.push((region, Arc::new(source)));
+        self
+    }
+
+    /// Applies all sources to the value of the cell at (x,y)
+    pub(crate) fn apply(&self, step: usize, position: (usize, usize), value: &mut T) {
+        for (region, source) in &self.sources {
+            if region.contains(position.0, position.1) {
+                source(step, position, value);
+            }
+        }
+    }
+
+    /// Whether any source overlaps the given region
+    pub(crate) fn touches(&self, other: &Region) -> bool {
+        self.sources.iter().any(|(region, _)| {
+            region.x.start < other.x.end
+                && other.x.start < region.x.end
+                && region.y.start < other.y.end
+                && other.y.start < region.y.end
+        })
+    }
+}
@@ -1,85 +1,253 @@
 use std::{
+    error::Error,
     fmt::{self, Debug},
     mem::swap,
-    path::PathBuf,
-    sync::{Arc, Barrier},
-    thread,
+    sync::Arc,
+    time::Instant,
 };
 
-use crate::{cell::Cell, vtk_writer::VtkWriter, withcall::WithCall, VtkOutput};
+use crate::{
+    cell::Cell,
+    controller::{Controller, SharedGrid},
+    executor::{Executor, ThreadExecutor},
+    forcing::Forcing,
+    integrator::{AdaptiveDt, AddScaled, Integrator},
+    operator::{Context, Operator},
+    order::UpdateOrder,
+    region::Region,
+    tiling::{self, Tiling},
+    view::GridView,
+    vtk_writer::VtkWriter,
+    withcall::WithCall,
+    IslParams, VtkOutput,
+};
 use parking_lot::{Mutex, RwLock};
 
 type NeighbourGrid<T> = Vec<Vec<Vec<Option<Arc<RwLock<T>>>>>>;
 
+/// Seconds spent computing each block, summed up until the domain is repartitioned
+type Timings = Option<Arc<Mutex<Vec<f64>>>>;
+
+/// The cells of a part of the domain, ordered by the traversal
+pub(crate) struct Block<T> {
+    pub(crate) region: Region,
+    pub(crate) cells: Vec<Cell<T>>,
+}
+
+/// Creates the cells of every region, `grid`, `neighbours` and `active` use the internal (swapped) coordinates.
+///
+/// Cells which are not `active` are left out and appear as missing neighbours.
+pub(crate) fn build_blocks<T>(
+    grid: &[Vec<Arc<RwLock<T>>>],
+    neighbours: &[(i8, i8)],
+    regions: Vec<Region>,
+    tiling: Option<&Tiling>,
+    update_order: &UpdateOrder,
+    active: Option<&[Vec<bool>]>,
+) -> Vec<Mutex<Block<T>>>
+where
+    T: Clone,
+{
+    let dimension = (grid.len(), grid[0].len());
+    let is_active = |i: usize, j: usize| active.is_none_or(|active| active[i][j]);
+    let mut nb_grid = populate(grid, dimension, neighbours, &is_active);
+
+    let some_val = grid[0][0].read().clone();
+
+    regions
+        .into_iter()
+        .map(|region| {
+            let tiles = match tiling {
+                Some(tiling) => tiling.split(&region),
+                None => vec![region.clone()],
+            };
+            let mut cells: Vec<Cell<T>> = Vec::with_capacity(region.len());
+            for tile in tiles {
+                for y in tile.y {
+                    for x in tile.x.clone().filter(|x| is_active(y, *x)) {
+                        cells.push(Cell {
+                            value: grid[y][x].clone(),
+                            neighbours: std::mem::take(&mut nb_grid[y][x]),
+                            next_val: some_val.clone(),
+                            colour: update_order.colour(x, y),
+                            position: (x, y),
+                            stages: vec![],
+                        });
+                    }
+                }
+            }
+            Mutex::new(Block { region, cells })
+        })
+        .collect()
+}
+
+fn populate<T>(
+    grid: &[Vec<Arc<RwLock<T>>>],
+    dimension: (usize, usize),
+    neighbours: &[(i8, i8)],
+    is_active: &dyn Fn(usize, usize) -> bool,
+) -> NeighbourGrid<T> {
+    let mut nb_grid = Vec::with_capacity(dimension.0);
+
+    for i in 0..dimension.0 {
+        let mut row = Vec::with_capacity(dimension.1);
+        for j in 0..dimension.1 {
+            let mut arr = Vec::with_capacity(neighbours.len());
+            for offset in neighbours.iter() {
+                let nb_pos = (i as i32 + offset.0 as i32, j as i32 + offset.1 as i32);
+                let nb = if nb_pos.0 < 0
+                    || nb_pos.1 < 0
+                    || nb_pos.0 >= dimension.0 as i32
+                    || nb_pos.1 >= dimension.1 as i32
+                    || !is_active(nb_pos.0 as usize, nb_pos.1 as usize)
+                {
+                    None
+                } else {
+                    Option::from((grid[nb_pos.0 as usize][nb_pos.1 as usize]).clone())
+                };
+                arr.push(nb);
+            }
+            row.push(arr);
+        }
+        nb_grid.push(row);
+    }
+
+    nb_grid
+}
+
 pub struct Grid<F, T>
 where
     T: Clone + VtkOutput,
 {
-    pub grid: Arc<Vec<Vec<Arc<RwLock<T>>>>>,
-    pub nb_grid: NeighbourGrid<T>,
+    pub grid: SharedGrid<T>,
+    blocks: Arc<Vec<Mutex<Block<T>>>>,
     op: WithCall<F, T>,
-    ext: (usize, usize),
-    runners: usize,
+    neighbours: Arc<Vec<(i8, i8)>>,
+    tiling: Option<Tiling>,
+    colours: usize,
+    integrator: Option<(Integrator, AddScaled<T>)>,
+    dt: f64,
+    adaptive_dt: Option<AdaptiveDt<T>>,
+    active: Option<Arc<Vec<Vec<bool>>>>,
+    forcing: Option<Forcing<T>>,
+    update_order: UpdateOrder,
+    rebalance_after: Option<usize>,
+    timings: Timings,
+    seed: u64,
+    time: f64,
+    executor: Box<dyn Executor>,
     dimension: (usize, usize),
     steps: usize,
     output_steps: usize,
-    neighbours: Vec<(i8, i8)>,
-    vtk_writer: Arc<Mutex<VtkWriter<T>>>,
-    some_val: T,
+    steps_done: usize,
+    vtk_writer: VtkWriter<T>,
+    controller: Option<Controller<T>>,
 }
 
 impl<F, T> Grid<F, T>
 where
-    F: Fn(&T, Vec<Option<&T>>) -> T + Clone + Send + Copy,
-    T: Clone + Send + Sync + VtkOutput,
+    F: Operator<T> + Clone + 'static,
+    T: Clone + Send + Sync + VtkOutput + 'static,
 {
-    fn compute_number_of_block_rows(number_of_processes: usize) -> usize {
-        let mut number_of_rows = (number_of_processes as f32).sqrt() as usize;
-        while number_of_processes % number_of_rows != 0 {
-            number_of_rows -= 1;
-        }
-        number_of_rows
-    }
-
-    pub fn new(
-        dimension: (usize, usize),
-        op: WithCall<F, T>,
-        runners: usize,
-        height: impl Fn(usize, usize) -> T,
-        steps: usize,
-        output_steps: usize,
-        mut neighbours: Vec<(i8, i8)>,
-        output_path: PathBuf,
-    ) -> Result<Self, InvalidThreadNumber> {
-        neighbours.iter_mut().for_each(|(x, y)| swap(x, y));
-
-        let dimension = (dimension.1, dimension.0);
+    pub fn new<H>(params: IslParams<T, F, H>) -> Result<Self, IslError>
+    where
+        H: Fn(usize, usize) -> T,
+    {
+        let IslParams {
+            dimension: user_dimension,
+            op,
+            runners,
+            height,
+            steps,
+            output_steps,
+            mut neighbours,
+            output_path,
+            controller,
+            executor,
+            tiling,
+            update_order,
+            dt,
+            adaptive_dt,
+            mask,
+            forcing,
+            cost,
+            rebalance_after,
+            decomposition,
+            seed,
+            centring,
+            integrator,
+        } = params;
+        let op = WithCall::new(op);
 
-        if dimension.0 * dimension.1 % runners != 0 {
-            return Err(InvalidThreadNumber {});
+        let colours = update_order.colours();
+        if colours == 0 {
+            return Err(IslError::InvalidParams(
+                "an update order needs at least one colour",
+            ));
+        }
+        if let UpdateOrder::MultiColour { colour_of, .. } = &update_order {
+            let (width, rows) = user_dimension;
+            if (0..rows).any(|y| (0..width).any(|x| colour_of(x, y) >= colours)) {
+                return Err(IslError::InvalidParams(
+                    "the colour of every cell must be below the number of colours",
+                ));
+            }
+        }
+        if tiling.is_some_and(|tiling| tiling.time_steps > 1) {
+            let unsupported = if colours > 1 {
+                Some("temporal blocking can not be combined with a coloured update order")
+            } else if integrator.is_some() {
+                Some("temporal blocking can not be combined with an integrator")
+            } else if adaptive_dt.is_some() {
+                Some("temporal blocking can not be combined with an adaptive dt")
+            } else if mask.is_some() {
+                Some("temporal blocking can not be combined with a mask")
+            } else if forcing.is_some() {
+                Some("temporal blocking can not be combined with a forcing")
+            } else {
+                None
+            };
+            if let Some(message) = unsupported {
+                return Err(IslError::InvalidParams(message));
+            }
+        }
+        if colours > 1 && integrator.is_some() {
+            return Err(IslError::InvalidParams(
+                "a coloured update order can not be combined with an integrator",
+            ));
+        }
+        if op.offsets().is_some_and(|offsets| offsets != neighbours) {
+            return Err(IslError::InvalidParams(
+                "the neighbours of IslParams must match the offsets of the operator",
+            ));
         }
+        op.check(user_dimension)?;
 
-        let number_of_blocks_y = Self::compute_number_of_block_rows(runners);
-        let number_of_blocks_x = runners / number_of_blocks_y;
+        let executor = executor.unwrap_or_else(|| {
+            Box::new(ThreadExecutor::new(runners).with_decomposition(decomposition))
+        });
+        let regions: Vec<Region> = if mask.is_some() || cost.is_some() {
+            executor.partition_by_cost(user_dimension, &|x, y| {
+                if mask.as_ref().is_none_or(|mask| mask(x, y)) {
+                    cost.as_ref().map_or(1.0, |cost| cost(x, y))
+                } else {
+                    0.0
+                }
+            })?
+        } else {
+            executor.partition(user_dimension)?
+        };
+
+        let user_neighbours = neighbours.clone();
+        neighbours.iter_mut().for_each(|(x, y)| swap(x, y));
 
-        let x_ext = dimension.0 / number_of_blocks_x;
-        let y_ext = dimension.1 / number_of_blocks_y;
+        let dimension = (user_dimension.1, user_dimension.0);
 
         let mut grid = Vec::with_capacity(dimension.0);
-        let mut gridn = Vec::with_capacity(dimension.0);
 
         for _ in 0..dimension.0 {
             grid.push(Vec::with_capacity(dimension.1));
         }
-        for _ in 0..dimension.0 {
-            gridn.push(Vec::with_capacity(dimension.1));
-        }
-
-        for row in gridn.iter_mut() {
-            for _ in 0..dimension.1 {
-                row.push(Vec::with_capacity(neighbours.len()));
-            }
-        }
 
         for (x, row) in grid.iter_mut().enumerate() {
             for y in 0..dimension.1 {
@@ -87,124 +255,417 @@ where
             }
         }
 
-        let writer = VtkWriter::new(output_path, T::value_names());
+        // indexed like the grid
+        let active: Option<Vec<Vec<bool>>> = mask.map(|mask| {
+            (0..dimension.0)
+                .map(|y| (0..dimension.1).map(|x| mask(x, y)).collect())
+                .collect()
+        });
+
+        let blocks = build_blocks(
+            &grid,
+            &neighbours,
+            regions,
+            tiling.as_ref(),
+            &update_order,
+            active.as_deref(),
+        );
+
+        let grid = Arc::new(grid);
+        if let Some(controller) = &controller {
+            controller.start(grid.clone());
+        }
+
+        let writer = VtkWriter::new(output_path, centring);
+        let timings = rebalance_after.map(|_| Arc::new(Mutex::new(vec![0.0; blocks.len()])));
 
-        let mut s = Self {
-            grid: Arc::new(grid),
-            nb_grid: gridn,
+        Ok(Self {
+            grid,
+            blocks: Arc::new(blocks),
             op,
-            ext: (x_ext, y_ext),
-            runners,
+            neighbours: Arc::new(user_neighbours),
+            tiling,
+            colours,
+            integrator,
+            dt,
+            adaptive_dt,
+            active: active.map(Arc::new),
+            forcing,
+            update_order,
+            rebalance_after,
+            timings,
+            seed,
+            time: 0.0,
+            executor,
             dimension,
             steps,
             output_steps,
-            neighbours,
-            vtk_writer: Arc::new(Mutex::new(writer)),
-            some_val: height(0, 0),
+            steps_done: 0,
+            vtk_writer: writer,
+            controller,
+        })
+    }
+
+    pub fn steps_done(&self) -> usize {
+        self.steps_done
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// The blocks the domain is currently split into, one per job of the executor
+    pub fn regions(&self) -> Vec<Region> {
+        self.blocks
+            .iter()
+            .map(|block| block.lock().region.clone())
+            .collect()
+    }
+
+    /// Number of steps calculated at once by temporal blocking
+    fn time_steps(&self) -> usize {
+        self.tiling.map_or(1, |tiling| tiling.time_steps)
+    }
+
+    /// Parks the caller while the controller, if any, pauses the simulation
+    fn checkpoint(&self) {
+        if let Some(controller) = &self.controller {
+            controller.checkpoint(self.steps_done);
+        }
+    }
+
+    /// Advances the simulation by a single step
+    pub fn step(&mut self) {
+        self.checkpoint();
+        self.advance(1);
+    }
+
+    /// Advances the simulation by `n` steps, in chunks of the configured temporal blocking
+    pub fn step_n(&mut self, n: usize) {
+        let mut remaining = n;
+        while remaining > 0 {
+            self.checkpoint();
+            let steps = self.time_steps().min(remaining);
+            self.advance(steps);
+            remaining -= steps;
+        }
+    }
+
+    /// Computes and commits the next `steps` steps in one go
+    fn advance(&mut self, steps: usize) {
+        if let Some(adaptive_dt) = self.adaptive_dt.clone() {
+            self.dt = self.reduce_dt(&adaptive_dt);
+        }
+        let context = Context::new(self.steps_done, self.time, self.dt).seeded(self.seed);
+
+        if self.colours > 1 {
+            for colour in 0..self.colours {
+                self.compute_colour(colour, context);
+                self.commit(Some(colour));
+            }
+        } else if let Some((integrator, add_scaled)) = self.integrator {
+            for stage in 0..integrator.stages() {
+                self.compute(Context {
+                    time: context.time + integrator.node(stage) * context.dt,
+                    ..context
+                });
+                self.integrate(integrator, stage, add_scaled);
+            }
+        } else if steps == 1 {
+            self.compute(context);
+            self.commit(None);
+        } else {
+            let blocks = self.blocks.clone();
+            let op = self.op.clone();
+            let grid = self.grid.clone();
+            let neighbours = self.neighbours.clone();
+            let tiling = self.tiling.unwrap();
+            let timings = self.timings.clone();
+            self.executor.run(
+                self.blocks.len(),
+                Arc::new(move |index| {
+                    let start = Instant::now();
+                    let block = &mut *blocks[index].lock();
+                    // cells are ordered tile by tile, in the same order as the values of each tile
+                    let mut cells = block.cells.iter_mut();
+                    for tile in tiling.split(&block.region) {
+                        let values =
+                            tiling::advance_tile(&grid, &tile, context, steps, &neighbours, &op);
+                        for (value, cell) in values.into_iter().zip(cells.by_ref()) {
+                            cell.next_val = value;
+                        }
+                    }
+                    record(&timings, index, start);
+                }),
+            );
+            self.commit(None);
+        }
+
+        if let Some(forcing) = self.forcing.clone() {
+            self.force(forcing);
+        }
+
+        self.steps_done += steps;
+        self.time += steps as f64 * self.dt;
+
+        let measured = self
+            .rebalance_after
+            .is_some_and(|after| self.steps_done >= after);
+        if measured && self.timings.is_some() {
+            self.rebalance();
+        }
+    }
+
+    /// Repartitions the domain by the measured compute time, each cell costs the average of its block
+    fn rebalance(&mut self) {
+        let Some(timings) = self.timings.take() else {
+            return;
         };
+        let seconds = timings.lock();
+        if seconds.iter().sum::<f64>() <= 0.0 {
+            return;
+        }
 
-        s.populate();
+        // indexed like the grid, inactive cells cost nothing
+        let mut costs = vec![vec![0.0; self.dimension.1]; self.dimension.0];
+        for (block, seconds) in self.blocks.iter().zip(seconds.iter()) {
+            let block = block.lock();
+            let per_cell = seconds / block.cells.len().max(1) as f64;
+            for cell in &block.cells {
+                costs[cell.position.1][cell.position.0] = per_cell;
+            }
+        }
+
+        let user_dimension = (self.dimension.1, self.dimension.0);
+        let Ok(regions) = self
+            .executor
+            .partition_by_cost(user_dimension, &|x, y| costs[y][x])
+        else {
+            // keep the current blocks if the domain can not be split by cost
+            return;
+        };
 
-        Ok(s)
+        let mut neighbours = (*self.neighbours).clone();
+        neighbours.iter_mut().for_each(|(x, y)| swap(x, y));
+        self.blocks = Arc::new(build_blocks(
+            &self.grid,
+            &neighbours,
+            regions,
+            self.tiling.as_ref(),
+            &self.update_order,
+            self.active.as_ref().map(|active| active.as_slice()),
+        ));
+    }
+
+    /// Reduces the measure of all cells to its maximum and derives the next `dt` from it
+    fn reduce_dt(&mut self, adaptive_dt: &AdaptiveDt<T>) -> f64 {
+        let maxima = Arc::new(Mutex::new(vec![f64::NEG_INFINITY; self.blocks.len()]));
+
+        let blocks = self.blocks.clone();
+        let measure = adaptive_dt.measure.clone();
+        let block_maxima = maxima.clone();
+        self.executor.run(
+            self.blocks.len(),
+            Arc::new(move |block| {
+                let max = blocks[block]
+                    .lock()
+                    .cells
+                    .iter()
+                    .map(|cell| measure(&cell.value.read()))
+                    .fold(f64::NEG_INFINITY, f64::max);
+                block_maxima.lock()[block] = max;
+            }),
+        );
+
+        let max = maxima
+            .lock()
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let dt = (adaptive_dt.dt_of)(max);
+        assert!(
+            dt.is_finite() && dt > 0.0,
+            "the adaptive dt must be positive and finite, got {dt}"
+        );
+        dt
     }
 
-    pub fn populate(&mut self) {
-        for i in 0..self.dimension.0 {
-            for j in 0..self.dimension.1 {
-                let mut arr = Vec::with_capacity(self.neighbours.len());
-                for offset in self.neighbours.iter() {
-                    let nb_pos = (i as i32 + offset.0 as i32, j as i32 + offset.1 as i32);
-                    let nb = if nb_pos.0 < 0
-                        || nb_pos.1 < 0
-                        || nb_pos.0 >= self.dimension.0 as i32
-                        || nb_pos.1 >= self.dimension.1 as i32
-                    {
+    /// Computes the next values of all cells from the current ones, tile by tile
+    fn compute(&mut self, context: Context) {
+        let blocks = self.blocks.clone();
+        let op = self.op.clone();
+        let grid = self.grid.clone();
+        let tiling = self.tiling;
+        // the fast path of the operator would read inactive cells as well
+        let masked = self.active.is_some();
+        let timings = self.timings.clone();
+        self.executor.run(
+            self.blocks.len(),
+            Arc::new(move |index| {
+                let start = Instant::now();
+                let block = &mut *blocks[index].lock();
+                let tiles = match &tiling {
+                    Some(tiling) => tiling.split(&block.region),
+                    None => vec![block.region.clone()],
+                };
+                let view = GridView::new(&grid);
+                let mut cells = block.cells.iter_mut().peekable();
+                for tile in tiles {
+                    let values = if masked {
                         None
                     } else {
-                        Option::from((self.grid[nb_pos.0 as usize][nb_pos.1 as usize]).clone())
+                        op.run_region(&view, &tile)
                     };
-                    arr.push(nb);
+                    match values {
+                        Some(values) => {
+                            for (value, cell) in values.into_iter().zip(cells.by_ref()) {
+                                cell.next_val = value;
+                            }
+                        }
+                        None => {
+                            let in_tile = |cell: &&mut Cell<T>| {
+                                tile.contains(cell.position.0, cell.position.1)
+                            };
+                            while let Some(cell) = cells.next_if(in_tile) {
+                                cell.run(&op, context);
+                            }
+                        }
+                    }
                 }
-                self.nb_grid[i][j] = arr;
-            }
-        }
+                record(&timings, index, start);
+            }),
+        );
     }
 
-    pub fn calculate(&mut self) {
-        let sync_lock = Arc::new(Barrier::new(self.runners));
-        let start_lock = Arc::new(Barrier::new(self.runners));
-        let write_lock = Arc::new(Barrier::new(self.runners));
-
-        thread::scope(|scope| {
-            let mut running_x = 0;
-            let mut running_y = 0;
-            let mut counter = 0;
-
-            let every_n_steps = self.steps / self.output_steps;
-
-            for rank in 0..self.runners {
-                let op1 = self.op.clone();
-                let start_lock = start_lock.clone();
-                let sync_lock = sync_lock.clone();
-                let write_lock = write_lock.clone();
-                let mut cells: Vec<Cell<T>> = vec![];
-                let grid = self.grid.clone();
-                let dimension = self.dimension;
-                let steps = self.steps;
-                let writer = self.vtk_writer.clone();
-                let some_val = self.some_val.clone();
-
-                for x in 0..self.ext.0 {
-                    for y in 0..self.ext.1 {
-                        cells.push(Cell {
-                            value: self.grid[running_x + x][running_y + y].clone(),
-                            neighbours: std::mem::take(
-                                &mut self.nb_grid[running_x + x][running_y + y],
-                            ),
-                            next_val: some_val.clone(),
-                        });
-                    }
+    /// Finishes a stage of the integrator, writing the values the next stage reads
+    fn integrate(&mut self, integrator: Integrator, stage: usize, add_scaled: AddScaled<T>) {
+        let blocks = self.blocks.clone();
+        let dt = self.dt;
+        self.executor.run(
+            self.blocks.len(),
+            Arc::new(move |block| {
+                for cell in blocks[block].lock().cells.iter_mut() {
+                    cell.integrate(&integrator, stage, dt, add_scaled);
                 }
-                running_x += self.ext.0;
+            }),
+        );
+    }
 
-                if running_x >= self.dimension.0 {
-                    running_x = 0;
-                    running_y += self.ext.1;
+    /// Computes the next values of all cells of one colour, reading the latest committed values
+    fn compute_colour(&mut self, colour: usize, context: Context) {
+        let blocks = self.blocks.clone();
+        let op = self.op.clone();
+        let timings = self.timings.clone();
+        self.executor.run(
+            self.blocks.len(),
+            Arc::new(move |block| {
+                let start = Instant::now();
+                for cell in blocks[block].lock().cells.iter_mut() {
+                    if cell.colour == colour {
+                        cell.run(&op, context);
+                    }
                 }
+                record(&timings, block, start);
+            }),
+        );
+    }
 
-                scope.spawn(move || {
-                    for _ in 0..steps {
-                        start_lock.wait();
-                        for cell in cells.iter_mut() {
-                            cell.run(&op1);
-                        }
-                        sync_lock.wait();
-                        for cell in cells.iter_mut() {
-                            cell.write();
-                        }
-                        write_lock.wait();
-                        if rank == 0 {
-                            counter += 1;
-                            if counter == every_n_steps {
-                                counter = 0;
-
-                                let mut out: Vec<Vec<T>> =
-                                    vec![vec![some_val.clone(); dimension.1]; dimension.0];
-                                for x in 0..dimension.0 {
-                                    for y in 0..dimension.1 {
-                                        out[x][y] = grid[x][y].read().clone();
-                                    }
-                                }
-                                {
-                                    let w = &mut *writer.lock();
-                                    w.write_step(out);
-                                }
-                            }
-                        }
+    /// Writes the computed values of all cells, or only those of the given colour
+    fn commit(&mut self, colour: Option<usize>) {
+        let blocks = self.blocks.clone();
+        self.executor.run(
+            self.blocks.len(),
+            Arc::new(move |block| {
+                for cell in blocks[block].lock().cells.iter_mut() {
+                    if colour.is_none_or(|colour| cell.colour == colour) {
+                        cell.write();
                     }
-                });
+                }
+            }),
+        );
+    }
+
+    /// Applies the sources to the committed values, every runner to the cells of its own block
+    fn force(&mut self, forcing: Forcing<T>) {
+        let blocks = self.blocks.clone();
+        let step = self.steps_done;
+        self.executor.run(
+            self.blocks.len(),
+            Arc::new(move |block| {
+                let block = &mut *blocks[block].lock();
+                if !forcing.touches(&block.region) {
+                    return;
+                }
+                for cell in block.cells.iter() {
+                    forcing.apply(step, cell.position, &mut cell.value.write());
+                }
+            }),
+        );
+    }
+
+    pub fn write_output(&mut self) {
+        let mut out: Vec<Vec<Option<T>>> = Vec::with_capacity(self.dimension.0);
+        for x in 0..self.dimension.0 {
+            let mut row = Vec::with_capacity(self.dimension.1);
+            for y in 0..self.dimension.1 {
+                let active = self.active.as_ref().is_none_or(|active| active[x][y]);
+                row.push(active.then(|| self.grid[x][y].read().clone()));
             }
+            out.push(row);
+        }
+        self.vtk_writer.write_step(out, self.time);
+    }
+
+    /// Runs all configured steps, writing output and honouring pause requests in between
+    pub fn calculate(&mut self) {
+        // finishes the controller on return and when an operator panics
+        let _finish = self.controller.as_ref().map(|controller| {
+            controller.start(self.grid.clone());
+            controller.finish_on_drop()
         });
+
+        let every_n_steps = self.steps.checked_div(self.output_steps).unwrap_or(0);
+
+        let mut step = 0;
+        while step < self.steps {
+            // every runner has committed the previous step, pause here if requested
+            self.checkpoint();
+
+            // temporal blocking may advance several steps, but never past the next output
+            let mut steps = self.time_steps().min(self.steps - step);
+            if every_n_steps > 0 {
+                steps = steps.min(every_n_steps - step % every_n_steps);
+            }
+            self.advance(steps);
+            step += steps;
+
+            if step.is_multiple_of(every_n_steps) {
+                self.write_output();
+            }
+        }
+    }
+}
+
+impl<F, T> Drop for Grid<F, T>
+where
+    T: Clone + VtkOutput,
+{
+    fn drop(&mut self) {
+        // release anyone waiting for a pause
+        if let Some(controller) = &self.controller {
+            controller.finish();
+        }
+    }
+}
+
+/// Adds the time since `start` to the compute time of the block, while it is measured
+fn record(timings: &Timings, block: usize, start: Instant) {
+    if let Some(timings) = timings {
+        timings.lock()[block] += start.elapsed().as_secs_f64();
     }
 }
 
@@ -213,9 +674,57 @@ pub struct InvalidThreadNumber();
 
 impl fmt::Display for InvalidThreadNumber {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Size of domain (x*y) was not divisible by the number of threads"
-        )
+        write!(f, "The domain could not be split into one block per thread")
+    }
+}
+
+impl Error for InvalidThreadNumber {}
+
+/// Reasons why a simulation could not be set up
+#[derive(Debug, Clone)]
+pub enum IslError {
+    /// The executor could not split the domain into its blocks
+    InvalidThreadNumber(InvalidThreadNumber),
+    /// The parameters contain settings which can not be used, or not be used together
+    InvalidParams(&'static str),
+    /// Data of the operator, e.g. the tags of an [OperatorTable](crate::OperatorTable), does not have the size of the domain
+    DimensionMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// A [Kernel](crate::Kernel) reads a field which was not bound with [Kernel::with_field](crate::Kernel::with_field)
+    UnboundField(String),
+}
+
+impl fmt::Display for IslError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IslError::InvalidThreadNumber(e) => fmt::Display::fmt(e, f),
+            IslError::InvalidParams(message) => write!(f, "Invalid parameters: {message}"),
+            IslError::DimensionMismatch { expected, found } => write!(
+                f,
+                "The operator was made for a domain of size {found:?}, not {expected:?}"
+            ),
+            IslError::UnboundField(name) => {
+                write!(f, "The field {name} is not bound to the kernel")
+            }
+        }
+    }
+}
+
+impl Error for IslError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IslError::InvalidThreadNumber(e) => Some(e),
+            IslError::InvalidParams(_)
+            | IslError::DimensionMismatch { .. }
+            | IslError::UnboundField(_) => None,
+        }
+    }
+}
+
+impl From<InvalidThreadNumber> for IslError {
+    fn from(e: InvalidThreadNumber) -> Self {
+        IslError::InvalidThreadNumber(e)
     }
 }
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+/// [State::add_scaled] of the cell type, kept as a plain function so the grid needs no `State` bound
+pub(crate) type AddScaled<T> = fn(&T, &T, f64) -> T;
+
+/// Values which can be combined linearly, as needed by the stages of an [Integrator].
+pub trait State: Clone {
+    /// Returns `self + factor * other`
+    fn add_scaled(&self, other: &Self, factor: f64) -> Self;
+}
+
+impl State for f32 {
+    fn add_scaled(&self, other: &Self, factor: f64) -> Self {
+        self + factor as f32 * other
+    }
+}
+
+impl State for f64 {
+    fn add_scaled(&self, other: &Self, factor: f64) -> Self {
+        self + factor * other
+    }
+}
+
+/// Explicit time integration of `du/dt = op(u)`.
+///
+/// Without an integrator, the operator returns the next value of each cell.
+/// With an integrator it returns the time derivative instead, and every step runs the stages
+/// of the chosen scheme with a time step of `dt`, see [IslParams::with_integrator](crate::IslParams::with_integrator).
+/// All cells finish a stage before the next stage reads their values.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// // heat equation, the operator returns the discrete laplacian
+/// let rhs = |num: &f64, nb: Vec<Option<&f64>>| nb.iter().map(|n| n.unwrap_or(num) - num).sum::<f64>();
+/// let neighbours = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+/// let params = IslParams::new((100, 100), rhs, 4, |x, _| x as f64, 100, 10, neighbours, PathBuf::from("raw"))
+///     .with_integrator(Integrator::Rk4)
+///     .with_dt(0.1);
+///
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    /// Forward Euler, one stage
+    Euler,
+    /// Heun's method, a second order Runge-Kutta scheme with two stages
+    Rk2,
+    /// The strong stability preserving Runge-Kutta scheme of third order, three stages
+    SspRk3,
+    /// The classic fourth order Runge-Kutta scheme, four stages
+    Rk4,
+}
+
+impl Integrator {
+    /// Number of evaluations of the operator per step
+    pub fn stages(&self) -> usize {
+        match self {
+            Integrator::Euler => 1,
+            Integrator::Rk2 => 2,
+            Integrator::SspRk3 => 3,
+            Integrator::Rk4 => 4,
+        }
+    }
+
+    /// Offset of the stage within the step, in units of `dt`
+    pub(crate) fn node(&self, stage: usize) -> f64 {
+        if stage == 0 {
+            0.0
+        } else {
+            self.weights(stage - 1).iter().sum()
+        }
+    }
+
+    /// Weights of the derivatives of stages `0..=stage`, which give the value for the following stage.
+    ///
+    /// After the last stage these are the weights of the final value.
+    pub(crate) fn weights(&self, stage: usize) -> &'static [f64] {
+        const EULER: [&[f64]; 1] = [&[1.0]];
+        const RK2: [&[f64]; 2] = [&[1.0], &[0.5, 0.5]];
+        const SSP_RK3: [&[f64]; 3] = [&[1.0], &[0.25, 0.25], &[1.0 / 6.0, 1.0 / 6.0, 2.0 / 3.0]];
+        const RK4: [&[f64]; 4] = [
+            &[0.5],
+            &[0.0, 0.5],
+            &[0.0, 0.0, 1.0],
+            &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+        ];
+
+        match self {
+            Integrator::Euler => EULER[stage],
+            Integrator::Rk2 => RK2[stage],
+            Integrator::SspRk3 => SSP_RK3[stage],
+            Integrator::Rk4 => RK4[stage],
+        }
+    }
+}
+
+/// Chooses `dt` before every step, from a global reduction over all cells.
+///
+/// Every runner applies `measure` to its cells, e.g. to compute the local wave speed, and
+/// the maximum over the whole domain is passed to `dt_of`, which returns the time step, e.g. from a CFL condition.
+/// The chosen `dt` is passed to the operator through its [Context](crate::Context).
+///
+/// # Example
+/// ```rust
+/// use rs_isl::AdaptiveDt;
+///
+/// let courant = 0.5;
+/// let adaptive = AdaptiveDt::new(
+///     |speed: &f32| speed.abs() as f64,
+///     move |max_speed| courant / max_speed.max(1e-6),
+/// );
+/// ```
+pub struct AdaptiveDt<T> {
+    pub(crate) measure: Arc<dyn Fn(&T) -> f64 + Send + Sync>,
+    pub(crate) dt_of: Arc<dyn Fn(f64) -> f64 + Send + Sync>,
+}
+
+impl<T> AdaptiveDt<T> {
+    pub fn new(
+        measure: impl Fn(&T) -> f64 + Send + Sync + 'static,
+        dt_of: impl Fn(f64) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            measure: Arc::new(measure),
+            dt_of: Arc::new(dt_of),
+        }
+    }
+}
+
+impl<T> Clone for AdaptiveDt<T> {
+    fn clone(&self) -> Self {
+        Self {
+            measure: self.measure.clone(),
+            dt_of: self.dt_of.clone(),
+        }
+    }
+}
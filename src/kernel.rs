@@ -0,0 +1,224 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    expr::{Expr, Var},
+    fields::Field,
+    grid::IslError,
+    linear::{LinearStencil, Scalar},
+    operator::{Context, Operator},
+    region::Region,
+    view::GridView,
+};
+
+type Compiled<T> = Arc<dyn Fn(&Context, &T, &[Option<&T>]) -> f64 + Send + Sync>;
+type FieldReader = Arc<dyn Fn(&Context, (i8, i8)) -> Option<f64> + Send + Sync>;
+
+/// An operator computing the next value of every cell from an [Expr].
+///
+/// The expression is constant-folded and compiled into closures once. Expressions which are weighted sums
+/// of cells run as a [LinearStencil], including its fast path over whole blocks.
+/// Fields of a [MultiField](crate::MultiField) read by the expression have to be bound with [Kernel::with_field],
+/// otherwise the simulation the kernel is given to returns [IslError::UnboundField].
+///
+/// The offsets of the kernel must be passed as the neighbours of [IslParams](crate::IslParams).
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// let heat: Expr = "u[0,0] + 0.2 * (u[-1,0] + u[1,0] + u[0,-1] + u[0,1] - 4.0 * u[0,0])".parse().unwrap();
+/// let kernel = Kernel::<f32>::new(heat);
+///
+/// let neighbours = kernel.neighbours();
+/// let params = IslParams::new((100, 100), kernel, 4, |x, _| x as f32, 100, 10, neighbours, PathBuf::from("raw"));
+/// run_isl(params).unwrap();
+/// ```
+pub struct Kernel<T> {
+    expr: Expr,
+    neighbours: Vec<(i8, i8)>,
+    linear: Option<LinearStencil<T>>,
+    fields: Vec<(String, FieldReader)>,
+    compiled: Compiled<T>,
+}
+
+impl<T> Clone for Kernel<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            expr: self.expr.clone(),
+            neighbours: self.neighbours.clone(),
+            linear: self.linear.clone(),
+            fields: self.fields.clone(),
+            compiled: self.compiled.clone(),
+        }
+    }
+}
+
+impl<T> Kernel<T>
+where
+    T: Scalar,
+{
+    pub fn new(expr: Expr) -> Self {
+        let expr = expr.fold();
+        let linear = expr.linear_terms().map(|(terms, constant)| {
+            let terms = terms
+                .into_iter()
+                .map(|(offset, weight)| (offset, T::from_f64(weight)))
+                .collect();
+            LinearStencil::new(terms).with_constant(T::from_f64(constant))
+        });
+        let neighbours = match &linear {
+            Some(linear) => linear.neighbours(),
+            None => expr.offsets(),
+        };
+
+        let mut kernel = Self {
+            compiled: Arc::new(|_, _, _| 0.0),
+            expr,
+            neighbours,
+            linear,
+            fields: vec![],
+        };
+        kernel.compiled = kernel.compile(&kernel.expr);
+        kernel
+    }
+
+    /// Lets the expression read the given field under its name
+    pub fn with_field<U>(mut self, field: &Field<U>) -> Self
+    where
+        U: Scalar,
+    {
+        let name = field.name().to_string();
+        let field = field.clone();
+        let reader: FieldReader = Arc::new(move |context: &Context, offset: (i8, i8)| {
+            field.get(context, offset).map(U::to_f64)
+        });
+        self.fields.retain(|(bound, _)| *bound != name);
+        self.fields.push((name, reader));
+        self.compiled = self.compile(&self.expr);
+        self
+    }
+
+    /// The offsets of the kernel, to be passed as the neighbours of [IslParams](crate::IslParams)
+    pub fn neighbours(&self) -> Vec<(i8, i8)> {
+        self.neighbours.clone()
+    }
+
+    /// The folded expression the kernel computes
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    fn compile(&self, expr: &Expr) -> Compiled<T> {
+        match expr {
+            Expr::Const(value) => {
+                let value = *value;
+                Arc::new(move |_, _, _| value)
+            }
+            Expr::Cell((0, 0)) => Arc::new(|_, value: &T, _| value.to_f64()),
+            Expr::Cell(offset) => {
+                let index = self.index(*offset);
+                Arc::new(move |_, _, neighbours: &[Option<&T>]| {
+                    neighbours[index].map_or(0.0, |value| value.to_f64())
+                })
+            }
+            Expr::Exists((0, 0)) => Arc::new(|_, _, _| 1.0),
+            Expr::Exists(offset) => {
+                let index = self.index(*offset);
+                Arc::new(move |_, _, neighbours: &[Option<&T>]| {
+                    if neighbours[index].is_some() {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+            }
+            Expr::Field(name, offset) => {
+                let offset = *offset;
+                match self.fields.iter().find(|(bound, _)| bound == name) {
+                    Some((_, reader)) => {
+                        let reader = reader.clone();
+                        Arc::new(move |context: &Context, _, _| {
+                            reader(context, offset).unwrap_or(0.0)
+                        })
+                    }
+                    None => {
+                        let name = name.clone();
+                        Arc::new(move |_, _, _| {
+                            panic!("the field {name} is not bound to the kernel")
+                        })
+                    }
+                }
+            }
+            Expr::Var(var) => {
+                let var = *var;
+                Arc::new(move |context: &Context, _, _| match var {
+                    Var::X => context.x as f64,
+                    Var::Y => context.y as f64,
+                    Var::Step => context.step as f64,
+                    Var::Time => context.time,
+                    Var::Dt => context.dt,
+                })
+            }
+            Expr::Unary(op, a) => {
+                let (op, a) = (*op, self.compile(a));
+                Arc::new(move |context, value, neighbours| op.apply(a(context, value, neighbours)))
+            }
+            Expr::Binary(op, a, b) => {
+                let (op, a, b) = (*op, self.compile(a), self.compile(b));
+                Arc::new(move |context, value, neighbours| {
+                    op.apply(a(context, value, neighbours), b(context, value, neighbours))
+                })
+            }
+        }
+    }
+
+    fn index(&self, offset: (i8, i8)) -> usize {
+        self.neighbours
+            .iter()
+            .position(|neighbour| *neighbour == offset)
+            .unwrap()
+    }
+}
+
+impl<T> Operator<T> for Kernel<T>
+where
+    T: Scalar,
+{
+    fn apply(&self, context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        match &self.linear {
+            Some(linear) => linear.apply(context, value, neighbours),
+            None => T::from_f64((self.compiled)(context, value, &neighbours)),
+        }
+    }
+
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        Some(self.neighbours())
+    }
+
+    fn check(&self, _dimension: (usize, usize)) -> Result<(), IslError> {
+        match self
+            .expr
+            .fields()
+            .into_iter()
+            .find(|name| self.fields.iter().all(|(bound, _)| bound != name))
+        {
+            Some(name) => Err(IslError::UnboundField(name.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    fn apply_region(&self, grid: &GridView<'_, T>, region: &Region) -> Option<Vec<T>> {
+        self.linear.as_ref()?.apply_region(grid, region)
+    }
+}
+
+impl<T> fmt::Display for Kernel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.expr.fmt(f)
+    }
+}
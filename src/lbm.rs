@@ -0,0 +1,332 @@
+//! A D2Q9 lattice Boltzmann solver for incompressible flows.
+//!
+//! Every cell holds the distributions of 9 discrete velocities. In each step the [Lattice] operator pulls the
+//! distributions streaming in from the 8 surrounding cells and relaxes them towards equilibrium (BGK collision).
+//! Obstacles are the inactive cells of a mask, see [IslParams::with_mask]: distributions streaming out of the fluid
+//! into them are reflected back (halfway bounce-back), which puts the no-slip wall halfway between the cells.
+//! What happens at the sides of the domain is chosen per side with [Lattice::with_edge].
+//!
+//! All quantities are in lattice units, i.e. a cell size and a step of 1. Velocities should stay well below 0.1.
+//!
+//! # Example
+//! ```rust, no_run
+//! use std::path::PathBuf;
+//!
+//! use rs_isl::{lbm::*, run_isl};
+//!
+//! // flow past a cylinder
+//! let lattice = Lattice::new((400, 100), 0.02)
+//!     .with_edge(Side::Left, Edge::Velocity(0.05, 0.0))
+//!     .with_edge(Side::Right, Edge::Outflow);
+//! let params = lattice
+//!     .params(4, |_, _| Distributions::equilibrium(1.0, (0.05, 0.0)), 10000, 100, PathBuf::from("raw"))
+//!     .with_mask(|x, y| (x as f32 - 80.0).powi(2) + (y as f32 - 50.0).powi(2) > 100.0);
+//! run_isl(params).unwrap();
+//! ```
+
+use std::path::PathBuf;
+
+use crate::{
+    operator::{Context, Operator},
+    IslError, IslParams, Value, ValueKind, VtkOutput,
+};
+
+/// The discrete velocities, resting first, then the 4 axes and the 4 diagonals
+const VELOCITIES: [(i8, i8); 9] = [
+    (0, 0),
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (0, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+    (1, -1),
+];
+const WEIGHTS: [f32; 9] = [
+    4.0 / 9.0,
+    1.0 / 9.0,
+    1.0 / 9.0,
+    1.0 / 9.0,
+    1.0 / 9.0,
+    1.0 / 36.0,
+    1.0 / 36.0,
+    1.0 / 36.0,
+    1.0 / 36.0,
+];
+/// The index of the velocity pointing the other way
+const OPPOSITE: [usize; 9] = [0, 3, 4, 1, 2, 7, 8, 5, 6];
+
+/// The distributions of a cell, `f[i]` moving with the i-th velocity of the lattice
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distributions {
+    pub f: [f32; 9],
+}
+
+impl Distributions {
+    /// The equilibrium distributions for the given density and velocity
+    pub fn equilibrium(density: f32, velocity: (f32, f32)) -> Self {
+        let (ux, uy) = velocity;
+        let uu = ux * ux + uy * uy;
+        Self {
+            f: std::array::from_fn(|i| {
+                let (cx, cy) = VELOCITIES[i];
+                let cu = cx as f32 * ux + cy as f32 * uy;
+                WEIGHTS[i] * density * (1.0 + 3.0 * cu + 4.5 * cu * cu - 1.5 * uu)
+            }),
+        }
+    }
+
+    pub fn density(&self) -> f32 {
+        self.f.iter().sum()
+    }
+
+    /// The velocity of the fluid, (x,y).
+    ///
+    /// Under a force of [Lattice::with_force], the distributions after a step move faster than the fluid
+    /// by half the force per density.
+    pub fn velocity(&self) -> (f32, f32) {
+        let (density, momentum) = self.moments();
+        (momentum.0 / density, momentum.1 / density)
+    }
+
+    fn moments(&self) -> (f32, (f32, f32)) {
+        let mut momentum = (0.0, 0.0);
+        for (f, (cx, cy)) in self.f.iter().zip(VELOCITIES) {
+            momentum.0 += f * cx as f32;
+            momentum.1 += f * cy as f32;
+        }
+        (self.density(), momentum)
+    }
+}
+
+impl Default for Distributions {
+    /// Fluid at rest with a density of 1
+    fn default() -> Self {
+        Self::equilibrium(1.0, (0.0, 0.0))
+    }
+}
+
+impl VtkOutput for Distributions {
+    fn value_names() -> Vec<String> {
+        vec!["density".into(), "velocity".into()]
+    }
+
+    fn value_kinds() -> Vec<ValueKind> {
+        vec![ValueKind::Scalar, ValueKind::Vector(2)]
+    }
+
+    fn cellvalue(&self) -> Vec<Value> {
+        let (ux, uy) = self.velocity();
+        vec![self.density().into(), ux.into(), uy.into()]
+    }
+}
+
+/// A side of the domain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// x = 0
+    Left,
+    /// The largest x
+    Right,
+    /// y = 0
+    Bottom,
+    /// The largest y
+    Top,
+}
+
+/// What enters the domain across one of its sides
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Edge {
+    /// A no-slip wall, distributions are reflected back
+    #[default]
+    Wall,
+    /// An inlet with the given velocity, (x,y). Distributions enter at equilibrium with the density of the cell.
+    Velocity(f32, f32),
+    /// A boundary with the given density, i.e. pressure. Distributions enter at equilibrium with the velocity of the cell.
+    Density(f32),
+    /// An outlet, distributions enter as if the flow continued unchanged beyond the side
+    Outflow,
+}
+
+/// The operator of the D2Q9 lattice Boltzmann method, streaming and BGK collision in one step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lattice {
+    dimension: (usize, usize),
+    tau: f32,
+    force: (f32, f32),
+    /// Left, right, bottom and top
+    edges: [Edge; 4],
+}
+
+impl Lattice {
+    /// A lattice of the given size, (x,y), for a fluid with the given kinematic viscosity.
+    /// All sides are walls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the viscosity is not positive.
+    pub fn new(dimension: (usize, usize), viscosity: f32) -> Self {
+        assert!(viscosity > 0.0, "the viscosity must be positive");
+        Self {
+            dimension,
+            tau: 3.0 * viscosity + 0.5,
+            force: (0.0, 0.0),
+            edges: [Edge::Wall; 4],
+        }
+    }
+
+    /// Choose what enters the domain across `side`
+    pub fn with_edge(mut self, side: Side, edge: Edge) -> Self {
+        self.edges[side as usize] = edge;
+        self
+    }
+
+    /// Accelerate the fluid by a constant force per volume, (x,y), e.g. gravity or a pressure gradient
+    pub fn with_force(mut self, force: (f32, f32)) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// The relaxation time of the collision
+    pub fn tau(&self) -> f32 {
+        self.tau
+    }
+
+    pub fn viscosity(&self) -> f32 {
+        (self.tau - 0.5) / 3.0
+    }
+
+    /// The neighbours of the lattice, the upstream cell of every velocity except the resting one
+    pub fn neighbours(&self) -> Vec<(i8, i8)> {
+        VELOCITIES[1..].iter().map(|(cx, cy)| (-cx, -cy)).collect()
+    }
+
+    /// Creates the parameters for running the lattice, see [IslParams::new]
+    pub fn params<H>(
+        self,
+        runners: usize,
+        init: H,
+        steps: usize,
+        output_steps: usize,
+        output_path: PathBuf,
+    ) -> IslParams<Distributions, Self, H>
+    where
+        H: Fn(usize, usize) -> Distributions,
+    {
+        let neighbours = self.neighbours();
+        IslParams::new(
+            self.dimension,
+            self,
+            runners,
+            init,
+            steps,
+            output_steps,
+            neighbours,
+            output_path,
+        )
+    }
+
+    /// The side of the domain the upstream cell of velocity `i` lies beyond, if any
+    fn outside(&self, context: &Context, i: usize) -> Option<Side> {
+        let (cx, cy) = VELOCITIES[i];
+        if cx > 0 && context.x == 0 {
+            Some(Side::Left)
+        } else if cx < 0 && context.x + 1 == self.dimension.0 {
+            Some(Side::Right)
+        } else if cy > 0 && context.y == 0 {
+            Some(Side::Bottom)
+        } else if cy < 0 && context.y + 1 == self.dimension.1 {
+            Some(Side::Top)
+        } else {
+            None
+        }
+    }
+}
+
+/// The distribution `i` entering across `side` when nothing changes across it,
+/// taken from the upstream cell along the side
+fn outflow(
+    side: Side,
+    i: usize,
+    cell: &Distributions,
+    neighbours: &[Option<&Distributions>],
+) -> f32 {
+    let (cx, cy) = VELOCITIES[i];
+    let along = match side {
+        Side::Left | Side::Right => (0, cy),
+        Side::Bottom | Side::Top => (cx, 0),
+    };
+    match VELOCITIES.iter().position(|velocity| *velocity == along) {
+        Some(0) | None => cell.f[i],
+        // in a corner the distribution comes from beyond the other side, which is treated as a wall
+        Some(beside) => neighbours[beside - 1].map_or(cell.f[OPPOSITE[i]], |beside| beside.f[i]),
+    }
+}
+
+impl Operator<Distributions> for Lattice {
+    fn apply(
+        &self,
+        context: &Context,
+        cell: &Distributions,
+        neighbours: Vec<Option<&Distributions>>,
+    ) -> Distributions {
+        // streaming, the distributions of the last step arrive from upstream
+        let mut streamed = cell.f;
+        for i in 1..9 {
+            streamed[i] = match neighbours[i - 1] {
+                Some(upstream) => upstream.f[i],
+                None => match self
+                    .outside(context, i)
+                    .map(|side| (side, self.edges[side as usize]))
+                {
+                    // a masked cell or a wall, the distribution which left this cell comes back
+                    None | Some((_, Edge::Wall)) => cell.f[OPPOSITE[i]],
+                    Some((_, Edge::Velocity(ux, uy))) => {
+                        Distributions::equilibrium(cell.density(), (ux, uy)).f[i]
+                    }
+                    Some((_, Edge::Density(density))) => {
+                        Distributions::equilibrium(density, cell.velocity()).f[i]
+                    }
+                    Some((side, Edge::Outflow)) => outflow(side, i, cell, &neighbours),
+                },
+            };
+        }
+
+        // BGK collision with the forcing of Guo et al.
+        let streamed = Distributions { f: streamed };
+        let (density, momentum) = streamed.moments();
+        let (fx, fy) = self.force;
+        let velocity = (
+            (momentum.0 + 0.5 * fx) / density,
+            (momentum.1 + 0.5 * fy) / density,
+        );
+        let equilibrium = Distributions::equilibrium(density, velocity);
+        let forcing = 1.0 - 0.5 / self.tau;
+
+        Distributions {
+            f: std::array::from_fn(|i| {
+                let (cx, cy) = (VELOCITIES[i].0 as f32, VELOCITIES[i].1 as f32);
+                let cu = cx * velocity.0 + cy * velocity.1;
+                let source = WEIGHTS[i]
+                    * ((3.0 * (cx - velocity.0) + 9.0 * cu * cx) * fx
+                        + (3.0 * (cy - velocity.1) + 9.0 * cu * cy) * fy);
+                streamed.f[i] - (streamed.f[i] - equilibrium.f[i]) / self.tau + forcing * source
+            }),
+        }
+    }
+
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        Some(self.neighbours())
+    }
+
+    fn check(&self, dimension: (usize, usize)) -> Result<(), IslError> {
+        if self.dimension != dimension {
+            return Err(IslError::DimensionMismatch {
+                expected: dimension,
+                found: self.dimension,
+            });
+        }
+        Ok(())
+    }
+}
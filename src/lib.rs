@@ -50,7 +50,7 @@
 // !     let params = IslParams::new(
 // !         dim,
 // !         op,
-// !         // number of threads for simulation, the domain size must be divisible by this number
+// !         // number of threads for simulation, every thread needs at least one row and column of the domain
 // !         10,
 // !         init,
 // !         // number of simulation steps
@@ -67,24 +67,96 @@
 // ! }
 // ! ```
 
-use grid::{Grid, InvalidThreadNumber};
 use std::path::PathBuf;
-use withcall::WithCall;
 
+use integrator::AddScaled;
+
+pub use automata::{count, Majority, Threshold, WithProbability};
+pub use controller::{Controller, PausedGrid};
+pub use distributed::DistributedSimulation;
+pub use executor::{Executor, Job, SerialExecutor, ThreadExecutor};
+pub use expr::{Binary, Expr, ParseExprError, Unary, Var};
+pub use fields::{Field, MultiField};
+pub use forcing::Forcing;
+pub use grid::{InvalidThreadNumber, IslError};
+pub use integrator::{AdaptiveDt, Integrator, State};
+pub use kernel::Kernel;
+pub use linear::{LinearStencil, Scalar};
+pub use operator::{Context, Contextual, Operator};
+pub use order::UpdateOrder;
+pub use region::{Decomposition, Region};
+pub use rng::CellRng;
+pub use simulation::Simulation;
+pub use table::OperatorTable;
+pub use tiling::Tiling;
+pub use transport::{Encode, SocketTransport, Transport};
+pub use view::{GridView, GridViewMut};
+pub use vtk_writer::{Centring, Value, ValueKind};
+
+#[cfg(feature = "rayon")]
+pub use rayon_executor::RayonExecutor;
+
+/// Derives [VtkOutput] for a struct, requires the `derive` feature.
+///
+/// Every field is written under its name, fields with several output values as `field.value`.
+/// Fields can be marked with `#[vtk(rename = "name")]`, `#[vtk(skip)]` and `#[vtk(vector)]`,
+/// which writes an array or tuple of 2 or 3 values as a vector.
+///
+/// ```rust
+/// use rs_isl::*;
+///
+/// #[derive(Clone, VtkOutput)]
+/// struct Flow {
+///     #[vtk(rename = "p")]
+///     pressure: f64,
+///     #[vtk(vector)]
+///     velocity: [f32; 2],
+///     #[vtk(skip)]
+///     iterations: usize,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use rs_isl_derive::VtkOutput;
+
+mod automata;
 mod cell;
+mod controller;
+mod distributed;
+mod executor;
+mod expr;
+mod fields;
+mod forcing;
 mod grid;
+mod integrator;
+mod kernel;
+pub mod lbm;
+mod linear;
+pub mod models;
+mod operator;
+mod order;
+mod pool;
+#[cfg(feature = "rayon")]
+mod rayon_executor;
+mod region;
+mod rng;
+mod simulation;
+mod table;
+mod tiling;
+mod transport;
+mod view;
 mod vtk_writer;
 mod withcall;
 
 /// Trait for defining the output of every cell.
 ///
-/// Implement for your Data Type to write your data into the output file
+/// Implement for your Data Type to write your data into the output file.
+/// Every value is written with its own type, e.g. f64 or u8, see [Value].
 ///
 /// # Example
 /// ```rust, no_run
-/// 
+///
 /// use rs_isl::*;
-/// 
+///
 /// struct Point {
 ///     x: u32,
 ///     y: u32,
@@ -94,8 +166,30 @@ mod withcall;
 ///     fn value_names() -> Vec<String> {
 ///         vec!["x_coord".into(), "y_coord".into()]
 ///     }
-///     fn cellvalue(&self) -> Vec<f32> {
-///         vec![self.x as f32, self.y as f32]
+///     fn cellvalue(&self) -> Vec<Value> {
+///         vec![self.x.into(), self.y.into()]
+///     }
+/// }
+/// ```
+///
+/// Values can also be written as vectors or tensors, e.g. to show them as glyphs:
+/// ```rust, no_run
+/// use rs_isl::*;
+///
+/// struct Flow {
+///     pressure: f32,
+///     velocity: (f32, f32),
+/// }
+///
+/// impl VtkOutput for Flow {
+///     fn value_names() -> Vec<String> {
+///         vec!["pressure".into(), "velocity".into()]
+///     }
+///     fn value_kinds() -> Vec<ValueKind> {
+///         vec![ValueKind::Scalar, ValueKind::Vector(2)]
+///     }
+///     fn cellvalue(&self) -> Vec<Value> {
+///         vec![self.pressure.into(), self.velocity.0.into(), self.velocity.1.into()]
 ///     }
 /// }
 /// ```
@@ -103,15 +197,23 @@ pub trait VtkOutput {
     /// Names for the DataArrays created with the values of every cell
     fn value_names() -> Vec<String>;
 
-    /// Values for every cell, these will be written to the DataArrays, identified by their name
-    fn cellvalue(&self) -> Vec<f32>;
+    /// Kinds of the DataArrays, one for every name. All of them are scalars by default.
+    fn value_kinds() -> Vec<ValueKind> {
+        vec![ValueKind::Scalar; Self::value_names().len()]
+    }
+
+    /// Values for every cell, these will be written to the DataArrays, identified by their name.
+    ///
+    /// Vectors and tensors take as many consecutive values as they have components, see [ValueKind::components].
+    /// All values of a DataArray must have the same type.
+    fn cellvalue(&self) -> Vec<Value>;
 }
 
 impl<T> VtkOutput for T
 where
-    T: Into<f32> + Clone,
+    T: Into<Value> + Clone,
 {
-    fn cellvalue(&self) -> Vec<f32> {
+    fn cellvalue(&self) -> Vec<Value> {
         vec![(*self).clone().into()]
     }
 
@@ -123,7 +225,7 @@ where
 pub struct IslParams<T, F, H>
 where
     T: Clone + Sync + Send,
-    F: Fn(&T, Vec<Option<&T>>) -> T + Clone + Send + Copy,
+    F: Operator<T> + Clone,
     H: Fn(usize, usize) -> T,
 {
     pub dimension: (usize, usize),
@@ -134,12 +236,26 @@ where
     pub output_steps: usize,
     pub neighbours: Vec<(i8, i8)>,
     pub output_path: PathBuf,
+    pub controller: Option<Controller<T>>,
+    pub executor: Option<Box<dyn Executor>>,
+    pub tiling: Option<Tiling>,
+    pub update_order: UpdateOrder,
+    pub dt: f64,
+    pub adaptive_dt: Option<AdaptiveDt<T>>,
+    pub mask: Option<Box<dyn Fn(usize, usize) -> bool + Send + Sync>>,
+    pub forcing: Option<Forcing<T>>,
+    pub cost: Option<Box<dyn Fn(usize, usize) -> f64 + Send + Sync>>,
+    pub rebalance_after: Option<usize>,
+    pub decomposition: Decomposition,
+    pub seed: u64,
+    pub centring: Centring,
+    integrator: Option<(Integrator, AddScaled<T>)>,
 }
 
 impl<T, F, H> IslParams<T, F, H>
 where
     T: Clone + Sync + Send,
-    F: Fn(&T, Vec<Option<&T>>) -> T + Clone + Send + Copy,
+    F: Operator<T> + Clone,
     H: Fn(usize, usize) -> T,
 {
     /// Set parameters for running an ISL
@@ -151,8 +267,9 @@ where
     /// * `steps` - Number of iterations.
     /// * `output_steps` - Number of output files returned.
     /// * `neighbours` - Definition of each cells neighbours, represented by their offsets.
-    /// * `output_type` - Whether to return raw data or formatted strings.
+    /// * `output_path` - Directory the output files are written to.
     ///
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         dimension: (usize, usize),
         operation: F,
@@ -172,8 +289,130 @@ where
             output_steps,
             neighbours,
             output_path,
+            controller: None,
+            executor: None,
+            tiling: None,
+            update_order: UpdateOrder::Jacobi,
+            dt: 1.0,
+            adaptive_dt: None,
+            mask: None,
+            forcing: None,
+            cost: None,
+            rebalance_after: None,
+            decomposition: Decomposition::Square,
+            seed: 0,
+            centring: Centring::Point,
+            integrator: None,
         }
     }
+
+    /// Attach a [Controller], which can pause the simulation between steps
+    pub fn with_controller(mut self, controller: Controller<T>) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Choose how the blocks of the grid are processed, by default a [ThreadExecutor] with `runners` threads is used.
+    ///
+    /// The number of runners is ignored when an executor is given.
+    pub fn with_executor(mut self, executor: impl Executor + 'static) -> Self {
+        self.executor = Some(Box::new(executor));
+        self
+    }
+
+    /// Arrange the blocks of the default [ThreadExecutor] as given, see [Decomposition]
+    pub fn with_decomposition(mut self, decomposition: Decomposition) -> Self {
+        self.decomposition = decomposition;
+        self
+    }
+
+    /// Traverse the cells of each block in tiles, see [Tiling]
+    pub fn with_tiling(mut self, tiling: Tiling) -> Self {
+        self.tiling = Some(tiling);
+        self
+    }
+
+    /// Update the cells colour by colour within each step, see [UpdateOrder]
+    pub fn with_update_order(mut self, update_order: UpdateOrder) -> Self {
+        self.update_order = update_order;
+        self
+    }
+
+    /// Treat the operator as the right-hand side `du/dt` and integrate it with the given scheme, see [Integrator]
+    pub fn with_integrator(mut self, integrator: Integrator) -> Self
+    where
+        T: State,
+    {
+        self.integrator = Some((integrator, T::add_scaled));
+        self
+    }
+
+    /// Set the time step used by the [Integrator], 1 by default
+    pub fn with_dt(mut self, dt: f64) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Choose the time step before every step, see [AdaptiveDt]. The `dt` given to [IslParams::with_dt] is ignored.
+    pub fn with_adaptive_dt(mut self, adaptive_dt: AdaptiveDt<T>) -> Self {
+        self.adaptive_dt = Some(adaptive_dt);
+        self
+    }
+
+    /// Only calculate the cells (x,y) for which `mask` returns true.
+    ///
+    /// Inactive cells keep their initial value, appear as missing neighbours (`None`) to the operator
+    /// and are written as NaN into the output. The default [ThreadExecutor] balances its blocks by the number of active cells.
+    /// Temporal blocking can not be combined with a mask.
+    pub fn with_mask(
+        mut self,
+        mask: impl Fn(usize, usize) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.mask = Some(Box::new(mask));
+        self
+    }
+
+    /// Like [IslParams::with_mask], with the active cells given as a bitmap indexed by `[x][y]`
+    pub fn with_mask_bitmap(self, bitmap: Vec<Vec<bool>>) -> Self {
+        self.with_mask(move |x, y| bitmap[x][y])
+    }
+
+    /// Apply the sources of a [Forcing] after every step.
+    ///
+    /// Temporal blocking can not be combined with a forcing.
+    pub fn with_forcing(mut self, forcing: Forcing<T>) -> Self {
+        self.forcing = Some(forcing);
+        self
+    }
+
+    /// Split the domain into blocks of about the same total cost, given per cell (x,y), instead of the same size.
+    ///
+    /// The cost is only used by executors which balance their blocks, like the default [ThreadExecutor].
+    /// Inactive cells of a mask cost nothing.
+    pub fn with_cost(mut self, cost: impl Fn(usize, usize) -> f64 + Send + Sync + 'static) -> Self {
+        self.cost = Some(Box::new(cost));
+        self
+    }
+
+    /// Measure the compute time of every block during the first `steps` steps and repartition the domain by it.
+    ///
+    /// Each cell is assigned the average time of the cells in its block, which replaces the cost of [IslParams::with_cost].
+    pub fn with_rebalancing(mut self, steps: usize) -> Self {
+        self.rebalance_after = Some(steps);
+        self
+    }
+
+    /// Seed the random numbers of [Context::rng], 0 by default
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Choose whether the output files hold point data, the default, or cell data, see [Centring]
+    pub fn with_centring(mut self, centring: Centring) -> Self {
+        self.centring = centring;
+        self
+    }
 }
 /// Runs the ISL and returns the output data
 ///
@@ -181,28 +420,27 @@ where
 ///
 /// # Errors
 ///
-/// If the given array size (x*y) is not divisible by the number of runners, an error will be returned.
-pub fn run_isl<T, F, H>(options: IslParams<T, F, H>) -> Result<(), InvalidThreadNumber>
+/// If the domain can not be split into one block per runner, e.g. because it has fewer rows than runners, an error will be returned.
+/// The same holds for settings which can not be combined, e.g. temporal blocking with an [Integrator], see [IslError].
+pub fn run_isl<T, F, H>(options: IslParams<T, F, H>) -> Result<(), IslError>
 where
-    T: Clone + Sync + Send + VtkOutput,
-    F: Fn(&T, Vec<Option<&T>>) -> T + Clone + Send + Copy,
+    T: Clone + Sync + Send + VtkOutput + 'static,
+    F: Operator<T> + Clone + 'static,
     H: Fn(usize, usize) -> T,
 {
-    let op = WithCall::new(options.op);
-
-    let r_grid = Grid::new(
-        options.dimension,
-        op,
-        options.runners,
-        options.height,
-        options.steps,
-        options.output_steps,
-        options.neighbours,
-        options.output_path,
-    );
-
-    match r_grid {
-        Ok(mut grid) => Ok(grid.calculate()),
-        Err(_) => Err(InvalidThreadNumber()),
+    let controller = options.controller.clone();
+
+    match Simulation::new(options) {
+        Ok(mut simulation) => {
+            simulation.run();
+            Ok(())
+        }
+        Err(e) => {
+            // release anyone waiting for a pause
+            if let Some(controller) = controller {
+                controller.finish();
+            }
+            Err(e)
+        }
     }
 }
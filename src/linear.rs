@@ -0,0 +1,173 @@
+use std::ops::{Add, Mul};
+
+use crate::{
+    operator::{Context, Operator},
+    region::Region,
+    view::GridView,
+};
+
+/// Floating point types supported by [LinearStencil] and [Kernel](crate::Kernel)
+pub trait Scalar: Copy + Send + Sync + Add<Output = Self> + Mul<Output = Self> + 'static {
+    const ZERO: Self;
+
+    fn from_f64(value: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// An operator which computes a weighted sum over the neighbours of each cell, plus a constant.
+///
+/// Neighbours outside of the domain do not contribute to the sum.
+/// Instead of calling a closure per cell, rs_isl copies each block into a contiguous buffer
+/// and applies the weights row by row, which allows the compiler to vectorise the inner loop.
+/// The results are the same as evaluating the sum cell by cell.
+///
+/// The offsets of the stencil must be passed as the neighbours of [IslParams](crate::IslParams).
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// // explicit heat diffusion
+/// let alpha = 0.2;
+/// let stencil = LinearStencil::new(vec![
+///     ((0, 0), 1.0 - 4.0 * alpha),
+///     ((-1, 0), alpha),
+///     ((1, 0), alpha),
+///     ((0, -1), alpha),
+///     ((0, 1), alpha),
+/// ]);
+///
+/// let neighbours = stencil.neighbours();
+/// let params = IslParams::new(
+///     (100, 100),
+///     stencil,
+///     4,
+///     |x, y| if x == 50 && y == 50 { 100.0f32 } else { 0.0 },
+///     100,
+///     10,
+///     neighbours,
+///     PathBuf::from("raw"),
+/// );
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearStencil<T> {
+    terms: Vec<((i8, i8), T)>,
+    constant: T,
+}
+
+impl<T> LinearStencil<T>
+where
+    T: Scalar,
+{
+    /// Creates a stencil from (offset, weight) pairs
+    pub fn new(terms: Vec<((i8, i8), T)>) -> Self {
+        Self {
+            terms,
+            constant: T::ZERO,
+        }
+    }
+
+    /// Adds a constant to every computed value
+    pub fn with_constant(mut self, constant: T) -> Self {
+        self.constant = constant;
+        self
+    }
+
+    /// The offsets of the stencil, to be passed as the neighbours of [IslParams](crate::IslParams)
+    pub fn neighbours(&self) -> Vec<(i8, i8)> {
+        self.terms.iter().map(|(offset, _)| *offset).collect()
+    }
+
+    fn radius(&self) -> usize {
+        self.terms
+            .iter()
+            .map(|((x, y), _)| x.unsigned_abs().max(y.unsigned_abs()) as usize)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<T> Operator<T> for LinearStencil<T>
+where
+    T: Scalar,
+{
+    fn apply(&self, _context: &Context, _value: &T, neighbours: Vec<Option<&T>>) -> T {
+        self.terms
+            .iter()
+            .zip(neighbours)
+            .fold(self.constant, |sum, ((_, weight), nb)| match nb {
+                Some(nb) => sum + *weight * *nb,
+                None => sum,
+            })
+    }
+
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        Some(self.neighbours())
+    }
+
+    fn apply_region(&self, grid: &GridView<'_, T>, region: &Region) -> Option<Vec<T>> {
+        let dimension = grid.dimension();
+        let radius = self.radius();
+        let width = region.x.len();
+        let height = region.y.len();
+        let padded_width = width + 2 * radius;
+
+        // copy the region and its halo, cells outside of the domain are zero
+        let mut input = Vec::with_capacity(padded_width * (height + 2 * radius));
+        for y in region.y.start as isize - radius as isize..(region.y.end + radius) as isize {
+            for x in region.x.start as isize - radius as isize..(region.x.end + radius) as isize {
+                let inside =
+                    x >= 0 && y >= 0 && (x as usize) < dimension.0 && (y as usize) < dimension.1;
+                input.push(if inside {
+                    grid.get(x as usize, y as usize)
+                } else {
+                    T::ZERO
+                });
+            }
+        }
+
+        let mut output = vec![self.constant; width * height];
+        for ((dx, dy), weight) in self.terms.iter() {
+            let weight = *weight;
+            for row in 0..height {
+                let start = (row as isize + radius as isize + *dy as isize) as usize * padded_width
+                    + (radius as isize + *dx as isize) as usize;
+                let source = &input[start..start + width];
+                let target = &mut output[row * width..(row + 1) * width];
+                for (out, value) in target.iter_mut().zip(source) {
+                    *out = *out + weight * *value;
+                }
+            }
+        }
+
+        Some(output)
+    }
+}
@@ -0,0 +1,486 @@
+//! Ready-to-use operators for classic stencil models.
+//!
+//! Every model knows its cell type and neighbours and creates the [IslParams] to run it with [Model::params].
+//! The parameters can be adjusted further like any others, e.g. with [IslParams::with_update_order].
+//!
+//! # Example
+//! ```rust, no_run
+//! use std::path::PathBuf;
+//!
+//! use rs_isl::{models::*, run_isl};
+//!
+//! let glider = |x: usize, y: usize| matches!((x, y), (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2));
+//! let params = Life::conway().params((64, 64), 4, glider, 200, 50, PathBuf::from("raw"));
+//! run_isl(params).unwrap();
+//! ```
+
+use std::{error::Error, fmt, path::PathBuf, str::FromStr, sync::Arc};
+
+use crate::{
+    operator::{Context, Operator},
+    IslParams, Value, VtkOutput,
+};
+
+const VON_NEUMANN: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const MOORE: [(i8, i8); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+/// An operator together with its cell type and neighbours
+pub trait Model: Clone + Sized {
+    type Cell: Clone + Send + Sync;
+
+    /// The neighbours the model reads, in the order its operator expects them
+    fn neighbours(&self) -> Vec<(i8, i8)>;
+
+    /// Creates the parameters for running the model, see [IslParams::new]
+    fn params<H>(
+        self,
+        dimension: (usize, usize),
+        runners: usize,
+        init: H,
+        steps: usize,
+        output_steps: usize,
+        output_path: PathBuf,
+    ) -> IslParams<Self::Cell, Self, H>
+    where
+        Self: Operator<Self::Cell>,
+        H: Fn(usize, usize) -> Self::Cell,
+    {
+        let neighbours = self.neighbours();
+        IslParams::new(
+            dimension,
+            self,
+            runners,
+            init,
+            steps,
+            output_steps,
+            neighbours,
+            output_path,
+        )
+    }
+}
+
+/// What the models see outside of the domain
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Boundary {
+    /// Missing neighbours have the value of the cell itself, so nothing flows across the boundary (Neumann)
+    #[default]
+    Reflecting,
+    /// Missing neighbours have the given value, like a surrounding held at a fixed temperature (Dirichlet)
+    Fixed(f32),
+}
+
+impl Boundary {
+    fn value(self, neighbour: Option<f32>, own: f32) -> f32 {
+        match (neighbour, self) {
+            (Some(value), _) => value,
+            (None, Boundary::Reflecting) => own,
+            (None, Boundary::Fixed(value)) => value,
+        }
+    }
+
+    /// The 5-point Laplacian of the value selected by `get`, with a grid spacing of 1
+    fn laplace<T>(self, own: &T, neighbours: &[Option<&T>], get: impl Fn(&T) -> f32) -> f32 {
+        let centre = get(own);
+        neighbours
+            .iter()
+            .map(|neighbour| self.value(neighbour.map(&get), centre) - centre)
+            .sum()
+    }
+}
+
+/// Life-like cellular automata on the Moore neighbourhood, given by the numbers of living neighbours
+/// for which a dead cell is born and a living cell survives.
+///
+/// Cells outside of the domain are dead. Rules can be parsed in the B/S notation, e.g. `"B36/S23"` for HighLife.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Life {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl Life {
+    /// A dead cell is born with a number of living neighbours in `birth`, a living one survives with one in `survival`
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let set = |counts: &[usize]| {
+            let mut set = [false; 9];
+            for count in counts {
+                assert!(*count <= 8, "a cell has at most 8 neighbours, not {count}");
+                set[*count] = true;
+            }
+            set
+        };
+        Self {
+            birth: set(birth),
+            survival: set(survival),
+        }
+    }
+
+    /// Conway's Game of Life, B3/S23
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+}
+
+impl fmt::Display for Life {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|count| set[*count])
+                .map(|count| count.to_string())
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+/// The error returned when parsing a [Life] rule fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRuleError {
+    pub rule: String,
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is not a rule in the form B3/S23", self.rule)
+    }
+}
+
+impl Error for ParseRuleError {}
+
+impl FromStr for Life {
+    type Err = ParseRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRuleError {
+            rule: rule.to_string(),
+        };
+        let counts = |part: &str, prefix: char| -> Result<Vec<usize>, ParseRuleError> {
+            let digits = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or_else(error)?;
+            digits
+                .chars()
+                .map(|digit| match digit.to_digit(10) {
+                    Some(count) if count <= 8 => Ok(count as usize),
+                    _ => Err(error()),
+                })
+                .collect()
+        };
+
+        let (birth, survival) = rule.trim().split_once('/').ok_or_else(error)?;
+        Ok(Self::new(&counts(birth, 'B')?, &counts(survival, 'S')?))
+    }
+}
+
+impl Operator<bool> for Life {
+    fn apply(&self, _context: &Context, alive: &bool, neighbours: Vec<Option<&bool>>) -> bool {
+        let living = neighbours.iter().flatten().filter(|alive| ***alive).count();
+        if *alive {
+            self.survival[living]
+        } else {
+            self.birth[living]
+        }
+    }
+}
+
+impl Model for Life {
+    type Cell = bool;
+
+    fn neighbours(&self) -> Vec<(i8, i8)> {
+        MOORE.to_vec()
+    }
+}
+
+/// Explicit heat diffusion, `u += alpha * laplace(u)` per step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Heat {
+    alpha: f32,
+    boundary: Boundary,
+}
+
+impl Heat {
+    /// Diffusion with the coefficient `alpha`, i.e. the diffusivity times dt / dx²
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is outside of the stable range `0..=0.25`.
+    pub fn new(alpha: f32) -> Self {
+        assert!(
+            (0.0..=0.25).contains(&alpha),
+            "the heat equation is unstable for alpha = {alpha}, it must be at most 0.25"
+        );
+        Self {
+            alpha,
+            boundary: Boundary::Reflecting,
+        }
+    }
+
+    /// Choose the boundary, [Boundary::Reflecting] (insulated) by default
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+}
+
+impl Operator<f32> for Heat {
+    fn apply(&self, _context: &Context, u: &f32, neighbours: Vec<Option<&f32>>) -> f32 {
+        u + self.alpha * self.boundary.laplace(u, &neighbours, |u| *u)
+    }
+}
+
+impl Model for Heat {
+    type Cell = f32;
+
+    fn neighbours(&self) -> Vec<(i8, i8)> {
+        VON_NEUMANN.to_vec()
+    }
+}
+
+/// A cell of the [Wave] equation, holding the displacement of the current and the previous step
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WaveCell {
+    pub u: f32,
+    pub previous: f32,
+}
+
+impl WaveCell {
+    /// A cell displaced by `u` without moving
+    pub fn at_rest(u: f32) -> Self {
+        Self { u, previous: u }
+    }
+}
+
+impl VtkOutput for WaveCell {
+    fn value_names() -> Vec<String> {
+        vec!["u".into(), "velocity".into()]
+    }
+
+    fn cellvalue(&self) -> Vec<Value> {
+        vec![self.u.into(), (self.u - self.previous).into()]
+    }
+}
+
+/// The wave equation, integrated with the leapfrog scheme `u' = 2u - u_prev + courant² * laplace(u)`
+///
+/// The two time levels of the leapfrog scheme are not kept in two fields of a [MultiField](crate::MultiField),
+/// but packed into one [WaveCell] holding `u` and its previous value, so that the model runs on a single grid
+/// like every other model. Only `u` is exchanged with the neighbours.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wave {
+    courant2: f32,
+    boundary: Boundary,
+}
+
+impl Wave {
+    /// Waves with the Courant number `c * dt / dx`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Courant number exceeds the stability limit of 1/√2.
+    pub fn new(courant: f32) -> Self {
+        let courant2 = courant * courant;
+        assert!(
+            courant2 <= 0.5,
+            "the wave equation is unstable for a Courant number of {courant}, it must be at most 1/√2"
+        );
+        Self {
+            courant2,
+            boundary: Boundary::Fixed(0.0),
+        }
+    }
+
+    /// Choose the boundary, [Boundary::Fixed] at 0 (reflecting with inverted phase, like a clamped membrane) by default
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+}
+
+impl Operator<WaveCell> for Wave {
+    fn apply(
+        &self,
+        _context: &Context,
+        cell: &WaveCell,
+        neighbours: Vec<Option<&WaveCell>>,
+    ) -> WaveCell {
+        let laplace = self.boundary.laplace(cell, &neighbours, |cell| cell.u);
+        WaveCell {
+            u: 2.0 * cell.u - cell.previous + self.courant2 * laplace,
+            previous: cell.u,
+        }
+    }
+}
+
+impl Model for Wave {
+    type Cell = WaveCell;
+
+    fn neighbours(&self) -> Vec<(i8, i8)> {
+        VON_NEUMANN.to_vec()
+    }
+}
+
+/// The concentrations of the two chemicals of the [GrayScott] model
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chemicals {
+    pub u: f32,
+    pub v: f32,
+}
+
+impl Default for Chemicals {
+    /// The stable state without any `v`
+    fn default() -> Self {
+        Self { u: 1.0, v: 0.0 }
+    }
+}
+
+impl VtkOutput for Chemicals {
+    fn value_names() -> Vec<String> {
+        vec!["u".into(), "v".into()]
+    }
+
+    fn cellvalue(&self) -> Vec<Value> {
+        vec![self.u.into(), self.v.into()]
+    }
+}
+
+/// Gray-Scott reaction diffusion, `u + 2v -> 3v`, with `u` fed and `v` removed at the given rates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GrayScott {
+    pub du: f32,
+    pub dv: f32,
+    pub feed: f32,
+    pub kill: f32,
+    boundary: Boundary,
+}
+
+impl GrayScott {
+    /// The model with the diffusion coefficients of `u` and `v` per step and the feed and kill rates
+    pub fn new(du: f32, dv: f32, feed: f32, kill: f32) -> Self {
+        assert!(
+            du <= 0.25 && dv <= 0.25,
+            "the diffusion of the Gray-Scott model is unstable for coefficients above 0.25"
+        );
+        Self {
+            du,
+            dv,
+            feed,
+            kill,
+            boundary: Boundary::Reflecting,
+        }
+    }
+
+    /// Growing, splitting spots
+    pub fn mitosis() -> Self {
+        Self::new(0.2, 0.1, 0.0367, 0.0649)
+    }
+
+    /// Meandering stripes
+    pub fn coral() -> Self {
+        Self::new(0.2, 0.1, 0.0545, 0.062)
+    }
+
+    /// Choose the boundary, [Boundary::Reflecting] by default. A fixed value applies to both chemicals.
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+}
+
+impl Operator<Chemicals> for GrayScott {
+    fn apply(
+        &self,
+        _context: &Context,
+        cell: &Chemicals,
+        neighbours: Vec<Option<&Chemicals>>,
+    ) -> Chemicals {
+        let Chemicals { u, v } = *cell;
+        let reaction = u * v * v;
+        Chemicals {
+            u: u + self.du * self.boundary.laplace(cell, &neighbours, |cell| cell.u) - reaction
+                + self.feed * (1.0 - u),
+            v: v + self.dv * self.boundary.laplace(cell, &neighbours, |cell| cell.v) + reaction
+                - (self.feed + self.kill) * v,
+        }
+    }
+}
+
+impl Model for GrayScott {
+    type Cell = Chemicals;
+
+    fn neighbours(&self) -> Vec<(i8, i8)> {
+        VON_NEUMANN.to_vec()
+    }
+}
+
+/// Jacobi iteration for the Poisson equation `laplace(u) = f`, or the Laplace equation without a source.
+///
+/// Every step replaces each cell by the average of its neighbours minus `h² f / 4`, which converges to the solution.
+/// With [UpdateOrder::RedBlack](crate::UpdateOrder::RedBlack) the iteration becomes Gauss-Seidel and converges about twice as fast.
+/// The boundary is [Boundary::Fixed] at 0 by default; the values of the cells outside of the domain are the Dirichlet boundary condition.
+#[derive(Clone)]
+pub struct Jacobi {
+    source: Option<Arc<dyn Fn(usize, usize) -> f32 + Send + Sync>>,
+    spacing: f32,
+    boundary: Boundary,
+}
+
+impl Jacobi {
+    /// Solves the Laplace equation
+    pub fn laplace() -> Self {
+        Self {
+            source: None,
+            spacing: 1.0,
+            boundary: Boundary::Fixed(0.0),
+        }
+    }
+
+    /// Solves the Poisson equation with the source `f` at the cell (x,y) and the grid spacing `h`
+    pub fn poisson(
+        source: impl Fn(usize, usize) -> f32 + Send + Sync + 'static,
+        spacing: f32,
+    ) -> Self {
+        Self {
+            source: Some(Arc::new(source)),
+            spacing,
+            boundary: Boundary::Fixed(0.0),
+        }
+    }
+
+    /// Choose the boundary, [Boundary::Fixed] at 0 by default
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+}
+
+impl Operator<f32> for Jacobi {
+    fn apply(&self, context: &Context, u: &f32, neighbours: Vec<Option<&f32>>) -> f32 {
+        let sum: f32 = neighbours
+            .iter()
+            .map(|neighbour| self.boundary.value(neighbour.copied(), *u))
+            .sum();
+        let source = self
+            .source
+            .as_ref()
+            .map_or(0.0, |source| source(context.x, context.y));
+        (sum - self.spacing * self.spacing * source) / 4.0
+    }
+}
+
+impl Model for Jacobi {
+    type Cell = f32;
+
+    fn neighbours(&self) -> Vec<(i8, i8)> {
+        VON_NEUMANN.to_vec()
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{grid::IslError, region::Region, rng::CellRng, view::GridView};
+
+/// Information about the cell which is currently calculated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Context {
+    /// Position of the cell, (x,y)
+    pub x: usize,
+    pub y: usize,
+    /// Number of steps calculated before the current one
+    pub step: usize,
+    /// Simulated time of the current step, or stage of an [Integrator](crate::Integrator)
+    pub time: f64,
+    /// Length of the current step in simulated time
+    pub dt: f64,
+    pub(crate) seed: u64,
+}
+
+impl Context {
+    pub(crate) fn new(step: usize, time: f64, dt: f64) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            step,
+            time,
+            dt,
+            seed: 0,
+        }
+    }
+
+    /// The same context in a simulation with the given seed
+    pub(crate) fn seeded(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// The same context for the cell at (x,y)
+    pub(crate) fn at(self, x: usize, y: usize) -> Self {
+        Self { x, y, ..self }
+    }
+
+    /// Random numbers for this cell and step.
+    ///
+    /// They are determined by the seed of [IslParams::with_seed](crate::IslParams::with_seed), the position of the cell
+    /// and the step, so a run gives the same results with any number of runners, executor or tiling.
+    /// The stages of an [Integrator](crate::Integrator) within one step see the same numbers.
+    pub fn rng(&self) -> CellRng {
+        self.rng_stream(0)
+    }
+
+    /// Like [Context::rng], one of several independent streams of random numbers for this cell and step.
+    ///
+    /// Combinators like [WithProbability](crate::WithProbability) draw from their own streams,
+    /// so the operators they wrap can use [Context::rng] without seeing correlated numbers.
+    pub fn rng_stream(&self, stream: u64) -> CellRng {
+        CellRng::new(self.seed, self.x, self.y, self.step, stream)
+    }
+}
+
+/// The operation calculating each cell's new value.
+///
+/// Implemented for every closure `Fn(&T, Vec<Option<&T>>) -> T`, which receives the current value of a cell
+/// and its neighbours in the order given by [IslParams::neighbours](crate::IslParams::neighbours).
+/// Neighbours outside of the domain are `None`.
+/// Closures which also need the [Context] of the cell can be wrapped in [Contextual].
+///
+/// Operators with more structure, like [LinearStencil](crate::LinearStencil), can additionally
+/// provide a faster way to compute a whole region at once.
+pub trait Operator<T>: Send + Sync {
+    /// Calculates the next value of a single cell
+    fn apply(&self, context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T;
+
+    /// The neighbour offsets this operator expects, if it defines them itself
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        None
+    }
+
+    /// Checks that the operator can run on a domain of the given size, (x,y), before the first step.
+    ///
+    /// Operators holding data per cell, like [OperatorTable](crate::OperatorTable), return an error if it has another size.
+    fn check(&self, dimension: (usize, usize)) -> Result<(), IslError> {
+        let _ = dimension;
+        Ok(())
+    }
+
+    /// Calculates the next values of all cells in `region` at once, in row-major order.
+    ///
+    /// Must produce the same values as calling [Operator::apply] for every cell.
+    /// Returns `None` if the operator has no such fast path, which is the default.
+    fn apply_region(&self, grid: &GridView<'_, T>, region: &Region) -> Option<Vec<T>> {
+        let _ = (grid, region);
+        None
+    }
+}
+
+impl<T, F> Operator<T> for F
+where
+    F: Fn(&T, Vec<Option<&T>>) -> T + Send + Sync,
+{
+    fn apply(&self, _context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        self(value, neighbours)
+    }
+}
+
+/// An operator from a closure `Fn(&Context, &T, Vec<Option<&T>>) -> T`, which depends on the position or step of a cell.
+///
+/// # Example
+/// ```rust
+/// use rs_isl::*;
+///
+/// // keep the left column fixed, shift everything else to the right
+/// let op = Contextual(|context: &Context, num: &f32, nb: Vec<Option<&f32>>| {
+///     if context.x == 0 { *num } else { *nb[0].unwrap() }
+/// });
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Contextual<F>(pub F);
+
+impl<T, F> Operator<T> for Contextual<F>
+where
+    F: Fn(&Context, &T, Vec<Option<&T>>) -> T + Send + Sync,
+{
+    fn apply(&self, context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        (self.0)(context, value, neighbours)
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+/// The order in which the cells of a step are updated.
+///
+/// With [UpdateOrder::Jacobi] every cell reads the values of the previous step.
+/// The other orders split the cells into colours, which are updated one after another within each step.
+/// Cells of a later colour therefore already read the new values of earlier colours, as in Gauss-Seidel or SOR.
+/// Cells of the same colour are still computed in parallel and read each other's old values.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::{path::PathBuf, sync::Arc};
+///
+/// use rs_isl::*;
+///
+/// // Gauss-Seidel for the Laplace equation, the left column is held at 1
+/// let relax = |_num: &f64, nb: Vec<Option<&f64>>| match nb[0] {
+///     None => 1.0,
+///     Some(_) => nb.iter().map(|n| n.copied().unwrap_or(0.0)).sum::<f64>() / 4.0,
+/// };
+/// let neighbours = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+/// let init = |_, _| 0.0;
+///
+/// // two half-sweeps per step, cells with an even x + y are updated first
+/// let params = IslParams::new((100, 100), relax, 4, init, 500, 5, neighbours.clone(), PathBuf::from("raw"))
+///     .with_update_order(UpdateOrder::RedBlack);
+/// run_isl(params).unwrap();
+///
+/// // four colours, e.g. for a stencil including diagonal neighbours
+/// let order = UpdateOrder::MultiColour {
+///     colours: 4,
+///     colour_of: Arc::new(|x, y| x % 2 + 2 * (y % 2)),
+/// };
+/// let params = IslParams::new((100, 100), relax, 4, init, 500, 5, neighbours, PathBuf::from("raw"))
+///     .with_update_order(order);
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub enum UpdateOrder {
+    /// All cells are updated at once from the values of the previous step
+    #[default]
+    Jacobi,
+    /// Checkerboard ordering, cells with an even x + y are updated before those with an odd x + y
+    RedBlack,
+    /// Cells are updated by ascending colour, `colour_of` maps (x,y) to a colour in `0..colours`.
+    /// Other colours are reported as [IslError::InvalidParams](crate::IslError::InvalidParams) when the grid is set up.
+    MultiColour {
+        colours: usize,
+        colour_of: Arc<dyn Fn(usize, usize) -> usize + Send + Sync>,
+    },
+}
+
+impl UpdateOrder {
+    /// Number of colours, i.e. update phases per step
+    pub fn colours(&self) -> usize {
+        match self {
+            UpdateOrder::Jacobi => 1,
+            UpdateOrder::RedBlack => 2,
+            UpdateOrder::MultiColour { colours, .. } => *colours,
+        }
+    }
+
+    /// The colour of the cell at (x,y)
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [UpdateOrder::MultiColour] returns a colour out of range.
+    pub fn colour(&self, x: usize, y: usize) -> usize {
+        match self {
+            UpdateOrder::Jacobi => 0,
+            UpdateOrder::RedBlack => (x + y) % 2,
+            UpdateOrder::MultiColour { colours, colour_of } => {
+                let colour = colour_of(x, y);
+                assert!(
+                    colour < *colours,
+                    "colour {colour} of cell ({x},{y}) is out of range"
+                );
+                colour
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Barrier},
+    thread::{self, JoinHandle},
+};
+
+use parking_lot::Mutex;
+
+use crate::executor::Job;
+
+/// A fixed set of threads, which stay alive between jobs.
+///
+/// Every job is run once by every worker, each worker passing its rank to the job.
+/// The caller is blocked until all workers have finished.
+pub struct WorkerPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+    job: Mutex<Option<Job>>,
+    start_lock: Barrier,
+    done_lock: Barrier,
+    panic: Mutex<Option<Box<dyn Any + Send>>>,
+}
+
+impl WorkerPool {
+    pub fn new(threads: usize) -> Self {
+        // the calling thread takes part in both barriers
+        let shared = Arc::new(Shared {
+            job: Mutex::new(None),
+            start_lock: Barrier::new(threads + 1),
+            done_lock: Barrier::new(threads + 1),
+            panic: Mutex::new(None),
+        });
+
+        let workers = (0..threads)
+            .map(|rank| {
+                let shared = shared.clone();
+                thread::spawn(move || loop {
+                    shared.start_lock.wait();
+
+                    let job = shared.job.lock().clone();
+                    let Some(job) = job else {
+                        break;
+                    };
+
+                    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| job(rank))) {
+                        shared.panic.lock().get_or_insert(payload);
+                    }
+                    drop(job);
+
+                    shared.done_lock.wait();
+                })
+            })
+            .collect();
+
+        Self { shared, workers }
+    }
+
+    /// Runs `job` on every worker and waits for all of them to finish.
+    ///
+    /// If the job panicked on any worker, the panic is resumed on the calling thread.
+    pub fn run(&self, job: Job) {
+        *self.shared.job.lock() = Some(job);
+        self.shared.start_lock.wait();
+        self.shared.done_lock.wait();
+        self.shared.job.lock().take();
+
+        if let Some(payload) = self.shared.panic.lock().take() {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // an empty job tells the workers to shut down
+        self.shared.job.lock().take();
+        self.shared.start_lock.wait();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use rayon::{
+    iter::{IntoParallelIterator, ParallelIterator},
+    ThreadPool,
+};
+
+use crate::{
+    executor::{Executor, Job},
+    grid::InvalidThreadNumber,
+    region::Region,
+};
+
+/// Runs the simulation on a rayon thread pool.
+///
+/// Instead of one block per runner the grid is split into rows, or tiles if configured,
+/// which are distributed over the pool using work stealing.
+/// If no pool is given, rayon's global pool is used.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::{path::PathBuf, sync::Arc};
+///
+/// use rs_isl::*;
+///
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+///
+/// let life = |alive: &bool, nb: Vec<Option<&bool>>| {
+///     let count = nb.iter().filter(|n| **n == Some(&true)).count();
+///     count == 3 || (*alive && count == 2)
+/// };
+/// let moore = vec![(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+/// let params = IslParams::new((512, 512), life, 4, |x, y| (x * y) % 3 == 0, 200, 20, moore, PathBuf::from("raw"))
+///     .with_executor(RayonExecutor::new().with_pool(Arc::new(pool)).with_tiles((32, 32)));
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct RayonExecutor {
+    pool: Option<Arc<ThreadPool>>,
+    tile_size: Option<(usize, usize)>,
+}
+
+impl RayonExecutor {
+    /// Processes the grid row by row on the global pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given pool instead of the global one
+    pub fn with_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Process tiles of the given size, (x,y), instead of rows.
+    /// Tiles at the upper borders of the domain may be smaller.
+    pub fn with_tiles(mut self, tile_size: (usize, usize)) -> Self {
+        assert!(
+            tile_size.0 > 0 && tile_size.1 > 0,
+            "tile size must not be zero"
+        );
+        self.tile_size = Some(tile_size);
+        self
+    }
+}
+
+impl Executor for RayonExecutor {
+    fn partition(&self, dimension: (usize, usize)) -> Result<Vec<Region>, InvalidThreadNumber> {
+        let tile_size = self.tile_size.unwrap_or((dimension.0, 1));
+        Ok(Region::tiles(dimension, tile_size))
+    }
+
+    fn run(&self, blocks: usize, job: Job) {
+        let run = || (0..blocks).into_par_iter().for_each(|block| job(block));
+
+        match &self.pool {
+            Some(pool) => pool.install(run),
+            None => run(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+use crate::grid::InvalidThreadNumber;
+
+/// How the domain is split into one block per runner.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// let diffuse = |num: &f32, nb: Vec<Option<&f32>>| num + 0.2 * nb.iter().map(|n| n.unwrap_or(num) - num).sum::<f32>();
+/// let neighbours = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+///
+/// // a wide and short domain, every runner gets a range of columns
+/// let executor = ThreadExecutor::new(8).with_decomposition(Decomposition::Columns);
+/// let params = IslParams::new((4000, 50), diffuse, 8, |x, _| (x % 2) as f32, 100, 10, neighbours.clone(), PathBuf::from("raw"))
+///     .with_executor(executor);
+/// run_isl(params).unwrap();
+///
+/// // 4 blocks along x times 2 along y
+/// let executor = ThreadExecutor::new(8).with_decomposition(Decomposition::Blocks(4, 2));
+/// let params = IslParams::new((800, 400), diffuse, 8, |x, _| (x % 2) as f32, 100, 10, neighbours, PathBuf::from("raw"))
+///     .with_executor(executor);
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Decomposition {
+    /// Blocks arranged as close to a square grid as possible, with no more blocks along x than along y
+    #[default]
+    Square,
+    /// The arrangement whose blocks are closest to squares, taking the aspect ratio of the domain into account
+    Auto,
+    /// Strips spanning the whole width of the domain, split along y
+    Rows,
+    /// Strips spanning the whole height of the domain, split along x
+    Columns,
+    /// Blocks arranged as the given number of blocks along x and y, whose product must be the number of runners
+    Blocks(usize, usize),
+}
+
+/// A rectangular part of the domain, in (x,y) coordinates.
+///
+/// The domain is split into blocks of this shape, which are processed independently by an [Executor](crate::Executor).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub x: Range<usize>,
+    pub y: Range<usize>,
+}
+
+impl Region {
+    /// The whole domain of the given size, (x,y)
+    pub fn whole(dimension: (usize, usize)) -> Self {
+        Self {
+            x: 0..dimension.0,
+            y: 0..dimension.1,
+        }
+    }
+
+    /// Number of cells in the region
+    pub fn len(&self) -> usize {
+        self.x.len() * self.y.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether (x,y) lies inside the region
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        self.x.contains(&x) && self.y.contains(&y)
+    }
+
+    fn compute_number_of_block_rows(number_of_processes: usize) -> usize {
+        let mut number_of_rows = (number_of_processes as f32).sqrt() as usize;
+        while !number_of_processes.is_multiple_of(number_of_rows) {
+            number_of_rows -= 1;
+        }
+        number_of_rows
+    }
+
+    /// Number of blocks along x and y for the given decomposition, every axis needs at least one cell per block
+    fn arrangement(
+        dimension: (usize, usize),
+        runners: usize,
+        decomposition: Decomposition,
+    ) -> Result<(usize, usize), InvalidThreadNumber> {
+        if runners == 0 {
+            return Err(InvalidThreadNumber {});
+        }
+
+        let blocks = match decomposition {
+            Decomposition::Square => {
+                let number_of_blocks_x = Self::compute_number_of_block_rows(runners);
+                (number_of_blocks_x, runners / number_of_blocks_x)
+            }
+            Decomposition::Auto => {
+                // the aspect ratio of a block, as its distance from a square
+                let skew = |(bx, by): (usize, usize)| {
+                    let ratio = (dimension.0 as f64 / bx as f64) / (dimension.1 as f64 / by as f64);
+                    ratio.ln().abs()
+                };
+                (1..=runners)
+                    .filter(|bx| runners.is_multiple_of(*bx))
+                    .map(|bx| (bx, runners / bx))
+                    .filter(|(bx, by)| *bx <= dimension.0 && *by <= dimension.1)
+                    .min_by(|a, b| skew(*a).total_cmp(&skew(*b)))
+                    .ok_or(InvalidThreadNumber {})?
+            }
+            Decomposition::Rows => (1, runners),
+            Decomposition::Columns => (runners, 1),
+            Decomposition::Blocks(bx, by) => {
+                if bx * by != runners {
+                    return Err(InvalidThreadNumber {});
+                }
+                (bx, by)
+            }
+        };
+
+        if dimension.0 < blocks.0 || dimension.1 < blocks.1 {
+            return Err(InvalidThreadNumber {});
+        }
+        Ok(blocks)
+    }
+
+    /// Splits `0..len` into `parts` ranges whose lengths differ by at most one
+    fn split_even(len: usize, parts: usize) -> Vec<Range<usize>> {
+        (0..parts)
+            .map(|part| part * len / parts..(part + 1) * len / parts)
+            .collect()
+    }
+
+    /// Splits the domain into `runners` blocks of about equal size, arranged as close to a square as possible
+    pub fn runner_blocks(
+        dimension: (usize, usize),
+        runners: usize,
+    ) -> Result<Vec<Region>, InvalidThreadNumber> {
+        Self::decompose(dimension, runners, Decomposition::Square)
+    }
+
+    /// Splits the domain into `runners` blocks arranged as given by the [Decomposition].
+    ///
+    /// If an axis is not divisible by the number of blocks along it, the blocks differ in size by one cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no runners, or if the domain can not be split into that many blocks,
+    /// e.g. because an axis is shorter than the number of blocks along it.
+    pub fn decompose(
+        dimension: (usize, usize),
+        runners: usize,
+        decomposition: Decomposition,
+    ) -> Result<Vec<Region>, InvalidThreadNumber> {
+        let (number_of_blocks_x, number_of_blocks_y) =
+            Self::arrangement(dimension, runners, decomposition)?;
+
+        let mut regions = Vec::with_capacity(runners);
+        for x in Self::split_even(dimension.0, number_of_blocks_x) {
+            for y in Self::split_even(dimension.1, number_of_blocks_y) {
+                regions.push(Region { x: x.clone(), y });
+            }
+        }
+        Ok(regions)
+    }
+
+    /// Splits the domain into `runners` blocks with about the same total `cost`, which is given per cell (x,y).
+    ///
+    /// The blocks are arranged like those of [Region::runner_blocks], but their borders are moved
+    /// so that expensive parts of the domain are shared by more blocks.
+    pub fn balanced_blocks(
+        dimension: (usize, usize),
+        runners: usize,
+        cost: &dyn Fn(usize, usize) -> f64,
+    ) -> Result<Vec<Region>, InvalidThreadNumber> {
+        Self::decompose_balanced(dimension, runners, Decomposition::Square, cost)
+    }
+
+    /// Like [Region::balanced_blocks], with the blocks arranged as given by the [Decomposition]
+    pub fn decompose_balanced(
+        dimension: (usize, usize),
+        runners: usize,
+        decomposition: Decomposition,
+        cost: &dyn Fn(usize, usize) -> f64,
+    ) -> Result<Vec<Region>, InvalidThreadNumber> {
+        let (number_of_blocks_x, number_of_blocks_y) =
+            Self::arrangement(dimension, runners, decomposition)?;
+
+        let column_costs: Vec<f64> = (0..dimension.0)
+            .map(|x| (0..dimension.1).map(|y| cost(x, y)).sum())
+            .collect();
+
+        let mut regions = Vec::with_capacity(runners);
+        for x in Self::split_balanced(&column_costs, number_of_blocks_x) {
+            let row_costs: Vec<f64> = (0..dimension.1)
+                .map(|y| x.clone().map(|x| cost(x, y)).sum())
+                .collect();
+            for y in Self::split_balanced(&row_costs, number_of_blocks_y) {
+                regions.push(Region { x: x.clone(), y });
+            }
+        }
+        Ok(regions)
+    }
+
+    /// Splits `0..costs.len()` into `parts` non-empty ranges with about the same sum of costs
+    fn split_balanced(costs: &[f64], parts: usize) -> Vec<Range<usize>> {
+        let total: f64 = costs.iter().sum();
+        let mut ranges = Vec::with_capacity(parts);
+        let mut start = 0;
+        let mut sum = 0.0;
+        let mut end = 0;
+        for part in 1..parts {
+            let target = total * part as f64 / parts as f64;
+            // leave at least one element for each of the remaining parts
+            let last = costs.len() - (parts - part);
+            while end < last && (end <= start || sum + costs[end] / 2.0 < target) {
+                sum += costs[end];
+                end += 1;
+            }
+            ranges.push(start..end);
+            start = end;
+        }
+        ranges.push(start..costs.len());
+        ranges
+    }
+
+    /// Splits the domain into tiles of the given size, (x,y). Tiles at the upper borders may be smaller.
+    pub fn tiles(dimension: (usize, usize), tile_size: (usize, usize)) -> Vec<Region> {
+        let mut regions = vec![];
+        for y in (0..dimension.1).step_by(tile_size.1) {
+            for x in (0..dimension.0).step_by(tile_size.0) {
+                regions.push(Region {
+                    x: x..(x + tile_size.0).min(dimension.0),
+                    y: y..(y + tile_size.1).min(dimension.1),
+                });
+            }
+        }
+        regions
+    }
+}
@@ -0,0 +1,64 @@
+/// A stream of random numbers for a single cell in a single step, see [Context::rng](crate::Context::rng).
+///
+/// The numbers only depend on the seed of the simulation, the position of the cell and the step,
+/// not on the runner computing the cell or the order of the cells. Every call to [Context::rng](crate::Context::rng)
+/// for the same cell and step starts the same stream again.
+///
+/// The generator is counter-based: the n-th number is a hash of the key of the cell and n.
+/// It is fast and well distributed, but not suited for cryptography.
+#[derive(Clone, Debug)]
+pub struct CellRng {
+    key: u64,
+    counter: u64,
+}
+
+impl CellRng {
+    pub(crate) fn new(seed: u64, x: usize, y: usize, step: usize, stream: u64) -> Self {
+        let key = [x as u64, y as u64, step as u64, stream]
+            .into_iter()
+            .fold(mix(seed), |key, part| mix(key ^ mix(part)));
+        Self { key, counter: 0 }
+    }
+
+    /// The next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.counter += 1;
+        mix(self
+            .key
+            .wrapping_add(self.counter.wrapping_mul(GOLDEN_GAMMA)))
+    }
+
+    /// A number uniformly distributed in `[0, 1)`
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// True with the probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.uniform() < p
+    }
+
+    /// A number uniformly distributed in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "can not draw a number below 0");
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+impl Iterator for CellRng {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        Some(self.next_u64())
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The finaliser of SplitMix64
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(GOLDEN_GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,112 @@
+use crate::{
+    grid::{Grid, IslError},
+    operator::Operator,
+    region::Region,
+    view::{GridView, GridViewMut},
+    IslParams, VtkOutput,
+};
+
+/// A simulation which is advanced step by step.
+///
+/// Unlike [run_isl](crate::run_isl), which runs all steps at once, a `Simulation` keeps its runners alive
+/// between calls. This allows embedding the ISL into a larger program, which inspects or modifies
+/// the grid in between steps.
+///
+/// A [Controller](crate::Controller) given to [IslParams::with_controller] can pause the simulation
+/// before every step, it is finished when the simulation is dropped.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// let op = |num: &f32, nb: Vec<Option<&f32>>| nb[0].copied().unwrap_or(*num);
+/// let params = IslParams::new((10, 10), op, 2, |x, _| x as f32, 0, 0, vec![(-1, 0)], PathBuf::from("raw"));
+///
+/// let mut sim = Simulation::new(params).unwrap();
+/// sim.step();
+/// sim.step_n(10);
+///
+/// let value = sim.view().get(5, 5);
+/// sim.view_mut().set(5, 5, value + 1.0);
+///
+/// sim.write_output();
+/// ```
+pub struct Simulation<T, F>
+where
+    T: Clone + VtkOutput,
+{
+    grid: Grid<F, T>,
+}
+
+impl<T, F> Simulation<T, F>
+where
+    T: Clone + Send + Sync + VtkOutput + 'static,
+    F: Operator<T> + Clone + 'static,
+{
+    /// Sets up the grid, the runners of the executor are started with the first step
+    ///
+    /// # Errors
+    ///
+    /// If the executor can not split the domain, e.g. because it has fewer rows than runners,
+    /// or if the parameters combine settings which can not be used together, see [IslError].
+    pub fn new<H>(params: IslParams<T, F, H>) -> Result<Self, IslError>
+    where
+        H: Fn(usize, usize) -> T,
+    {
+        Ok(Self {
+            grid: Grid::new(params)?,
+        })
+    }
+
+    /// Advances the simulation by one step
+    pub fn step(&mut self) {
+        self.grid.step();
+    }
+
+    /// Advances the simulation by `n` steps
+    pub fn step_n(&mut self, n: usize) {
+        self.grid.step_n(n);
+    }
+
+    /// Number of steps calculated so far
+    pub fn steps_done(&self) -> usize {
+        self.grid.steps_done()
+    }
+
+    /// Simulated time so far, the sum of the `dt` of all steps
+    pub fn time(&self) -> f64 {
+        self.grid.time()
+    }
+
+    /// The `dt` of the latest step
+    pub fn dt(&self) -> f64 {
+        self.grid.dt()
+    }
+
+    /// The blocks the domain is split into, in the order they are passed to the [Executor](crate::Executor)
+    pub fn regions(&self) -> Vec<Region> {
+        self.grid.regions()
+    }
+
+    /// Read access to the current values
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView::new(&self.grid.grid)
+    }
+
+    /// Read and write access to the current values
+    pub fn view_mut(&mut self) -> GridViewMut<'_, T> {
+        GridViewMut::new(&self.grid.grid)
+    }
+
+    /// Writes the current values into the next output file
+    pub fn write_output(&mut self) {
+        self.grid.write_output();
+    }
+
+    /// Runs the number of steps given in [IslParams], writing output files as configured there
+    pub fn run(&mut self) {
+        self.grid.calculate();
+    }
+}
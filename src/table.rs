@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use crate::{
+    grid::IslError,
+    operator::{Context, Operator},
+    region::Region,
+    view::GridView,
+};
+
+/// An operator which dispatches every cell to the operator registered for its tag.
+///
+/// The tags form a grid of small integers, e.g. to mark a source region, an absorbing layer and the bulk medium.
+/// Cells whose tag has no operator keep their value.
+/// All operators receive the neighbours given to [IslParams](crate::IslParams).
+/// The size of the tag grid is checked against the domain when the simulation is set up.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// const BULK: u8 = 0;
+/// const ABSORBING: u8 = 1;
+///
+/// let dimension = (100, 50);
+/// let table = OperatorTable::new(dimension, |x, _y| if x >= 90 { ABSORBING } else { BULK })
+///     .with_operator(BULK, |_num: &f32, nb: Vec<Option<&f32>>| nb[0].copied().unwrap_or(0.0))
+///     .with_operator(ABSORBING, |num: &f32, _nb: Vec<Option<&f32>>| num * 0.5);
+///
+/// let params = IslParams::new(dimension, table, 2, |x, _| x as f32, 100, 10, vec![(-1, 0)], PathBuf::from("raw"));
+/// run_isl(params).unwrap();
+/// ```
+pub struct OperatorTable<T> {
+    dimension: (usize, usize),
+    tags: Arc<Vec<Vec<u8>>>,
+    operators: Vec<Option<Arc<dyn Operator<T>>>>,
+}
+
+impl<T> Clone for OperatorTable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            dimension: self.dimension,
+            tags: self.tags.clone(),
+            operators: self.operators.clone(),
+        }
+    }
+}
+
+impl<T> OperatorTable<T> {
+    /// Creates a table without operators for a domain of the given size, (x,y), tagging every cell with `tag(x, y)`
+    pub fn new(dimension: (usize, usize), tag: impl Fn(usize, usize) -> u8) -> Self {
+        let tags = (0..dimension.1)
+            .map(|y| (0..dimension.0).map(|x| tag(x, y)).collect())
+            .collect();
+        Self {
+            dimension,
+            tags: Arc::new(tags),
+            operators: vec![],
+        }
+    }
+
+    /// Like [OperatorTable::new], with the tags given as a grid indexed by `[x][y]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the columns `tags[x]` differ in length.
+    pub fn from_tags(tags: Vec<Vec<u8>>) -> Self {
+        let height = tags.first().map_or(0, Vec::len);
+        assert!(
+            tags.iter().all(|column| column.len() == height),
+            "all columns of the tags must have the same length"
+        );
+        Self::new((tags.len(), height), |x, y| tags[x][y])
+    }
+
+    /// Registers the operator for all cells with the given tag
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operator defines offsets which differ from those of another operator in the table.
+    pub fn with_operator(mut self, tag: u8, operator: impl Operator<T> + 'static) -> Self {
+        if let (Some(offsets), Some(other)) = (operator.offsets(), self.common_offsets()) {
+            assert_eq!(
+                offsets, other,
+                "all operators of a table must use the same offsets"
+            );
+        }
+
+        let tag = tag as usize;
+        if self.operators.len() <= tag {
+            self.operators.resize(tag + 1, None);
+        }
+        self.operators[tag] = Some(Arc::new(operator));
+        self
+    }
+
+    /// The tag of the cell at (x,y)
+    pub fn tag(&self, x: usize, y: usize) -> u8 {
+        self.tags[y][x]
+    }
+
+    fn common_offsets(&self) -> Option<Vec<(i8, i8)>> {
+        self.operators
+            .iter()
+            .flatten()
+            .find_map(|operator| operator.offsets())
+    }
+
+    fn operator(&self, tag: u8) -> Option<&dyn Operator<T>> {
+        self.operators.get(tag as usize)?.as_deref()
+    }
+}
+
+impl<T> Operator<T> for OperatorTable<T>
+where
+    T: Clone + Send + Sync,
+{
+    fn apply(&self, context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        match self.operator(self.tag(context.x, context.y)) {
+            Some(operator) => operator.apply(context, value, neighbours),
+            None => value.clone(),
+        }
+    }
+
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        self.common_offsets()
+    }
+
+    fn check(&self, dimension: (usize, usize)) -> Result<(), IslError> {
+        if self.dimension != dimension {
+            return Err(IslError::DimensionMismatch {
+                expected: dimension,
+                found: self.dimension,
+            });
+        }
+        self.operators
+            .iter()
+            .flatten()
+            .try_for_each(|operator| operator.check(dimension))
+    }
+
+    fn apply_region(&self, grid: &GridView<'_, T>, region: &Region) -> Option<Vec<T>> {
+        // regions with a single tag can use the fast path of their operator
+        let tag = self.tag(region.x.start, region.y.start);
+        let uniform = region
+            .y
+            .clone()
+            .all(|y| region.x.clone().all(|x| self.tag(x, y) == tag));
+        if uniform {
+            self.operator(tag)?.apply_region(grid, region)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    operator::{Context, Operator},
+    region::Region,
+    withcall::WithCall,
+};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Cache-blocked traversal of the cells within each block.
+///
+/// Instead of sweeping whole rows of a block, its cells are processed tile by tile.
+/// Optionally several steps are calculated per tile at once (temporal blocking): each tile
+/// copies its surroundings with a halo of `time_steps` times the stencil radius and advances
+/// this copy locally, trading redundant work at the halo for fewer passes over memory.
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// let smooth = |num: &f32, nb: Vec<Option<&f32>>| {
+///     (num + nb.iter().map(|n| n.unwrap_or(num)).sum::<f32>()) / 5.0
+/// };
+/// let neighbours = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+///
+/// // 64x16 tiles, advancing 4 steps at a time
+/// let params = IslParams::new((1024, 1024), smooth, 8, |x, y| ((x ^ y) % 7) as f32, 400, 4, neighbours, PathBuf::from("raw"))
+///     .with_tiling(Tiling::new((64, 16)).with_time_steps(4));
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tiling {
+    pub size: (usize, usize),
+    pub time_steps: usize,
+}
+
+impl Tiling {
+    /// Traverse tiles of the given size, (x,y), one step at a time
+    pub fn new(size: (usize, usize)) -> Self {
+        assert!(size.0 > 0 && size.1 > 0, "tile size must not be zero");
+        Self {
+            size,
+            time_steps: 1,
+        }
+    }
+
+    /// Calculate the given number of steps per tile before moving on
+    pub fn with_time_steps(mut self, time_steps: usize) -> Self {
+        assert!(time_steps > 0, "number of time steps must not be zero");
+        self.time_steps = time_steps;
+        self
+    }
+
+    /// Splits a block into tiles, tiles at the upper borders of the block may be smaller
+    pub(crate) fn split(&self, block: &Region) -> Vec<Region> {
+        let mut tiles = vec![];
+        for y in block.y.clone().step_by(self.size.1) {
+            for x in block.x.clone().step_by(self.size.0) {
+                tiles.push(Region {
+                    x: x..(x + self.size.0).min(block.x.end),
+                    y: y..(y + self.size.1).min(block.y.end),
+                });
+            }
+        }
+        tiles
+    }
+}
+
+/// Advances a copy of `tile` and its surroundings by `steps` steps, starting with the step described by `context`.
+///
+/// Returns the new values of the tile's cells in row-major order.
+/// Reads from `grid` only, so it may run concurrently for all tiles of the domain.
+pub(crate) fn advance_tile<F, T>(
+    grid: &[Vec<Arc<RwLock<T>>>],
+    tile: &Region,
+    context: Context,
+    steps: usize,
+    neighbours: &[(i8, i8)],
+    op: &WithCall<F, T>,
+) -> Vec<T>
+where
+    F: Operator<T>,
+    T: Clone,
+{
+    let dimension = (grid[0].len() as isize, grid.len() as isize);
+    let radius = neighbours
+        .iter()
+        .map(|(x, y)| x.unsigned_abs().max(y.unsigned_abs()) as usize)
+        .max()
+        .unwrap_or(0);
+    let halo = steps * radius;
+
+    let width = tile.x.len() + 2 * halo;
+    let height = tile.y.len() + 2 * halo;
+    let origin = (
+        tile.x.start as isize - halo as isize,
+        tile.y.start as isize - halo as isize,
+    );
+    let inside = |x: usize, y: usize| {
+        let global = (origin.0 + x as isize, origin.1 + y as isize);
+        global.0 >= 0 && global.1 >= 0 && global.0 < dimension.0 && global.1 < dimension.1
+    };
+
+    // cells outside of the domain are None, just like missing neighbours
+    let mut current: Vec<Option<T>> = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            current.push(inside(x, y).then(|| {
+                let global = (
+                    (origin.0 + x as isize) as usize,
+                    (origin.1 + y as isize) as usize,
+                );
+                grid[global.1][global.0].read().clone()
+            }));
+        }
+    }
+
+    for step in 1..=steps {
+        // values within this margin depend on cells outside of the copy and are no longer valid
+        let margin = step * radius;
+        let mut next: Vec<Option<T>> = vec![None; width * height];
+
+        for y in margin..height - margin {
+            for x in margin..width - margin {
+                let Some(own) = &current[y * width + x] else {
+                    continue;
+                };
+                let nbs = neighbours
+                    .iter()
+                    .map(|(dx, dy)| {
+                        let nb = (x as isize + *dx as isize, y as isize + *dy as isize);
+                        current[nb.1 as usize * width + nb.0 as usize].as_ref()
+                    })
+                    .collect();
+                let context = Context {
+                    step: context.step + step - 1,
+                    time: context.time + (step - 1) as f64 * context.dt,
+                    ..context
+                }
+                .at(
+                    (origin.0 + x as isize) as usize,
+                    (origin.1 + y as isize) as usize,
+                );
+                next[y * width + x] = Some(op.run(&context, own, nbs));
+            }
+        }
+        current = next;
+    }
+
+    let mut values = Vec::with_capacity(tile.len());
+    for y in halo..halo + tile.y.len() {
+        for x in halo..halo + tile.x.len() {
+            values.push(current[y * width + x].take().unwrap());
+        }
+    }
+    values
+}
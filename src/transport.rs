@@ -0,0 +1,318 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+};
+
+/// How often a rank tries to reach another one which has not started listening yet
+const CONNECT_ATTEMPTS: usize = 200;
+const CONNECT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Largest message a [SocketTransport] receives by default, 1 GiB
+const MAX_MESSAGE_LEN: usize = 1 << 30;
+
+/// Exchanges messages between the processes of a [DistributedSimulation](crate::DistributedSimulation).
+///
+/// Every process has a rank in `0..size`. Messages between two ranks arrive in the order they were sent.
+pub trait Transport: Send {
+    /// Rank of this process
+    fn rank(&self) -> usize;
+
+    /// Number of processes
+    fn size(&self) -> usize;
+
+    /// Sends a message to the process with the given rank
+    fn send(&mut self, to: usize, message: &[u8]) -> io::Result<()>;
+
+    /// Waits for the next message from the process with the given rank
+    fn receive(&mut self, from: usize) -> io::Result<Vec<u8>>;
+}
+
+/// A [Transport] with one stream socket to every other process, e.g. TCP or Unix domain sockets.
+///
+/// Every rank listens on its own address and connects to all lower ranks, so all ranks have to be started
+/// with the same list of addresses. Ranks which are not listening yet are retried for a few seconds.
+/// Messages announced as longer than [SocketTransport::with_max_message_len] are rejected before they are read.
+///
+/// # Example
+/// ```rust, no_run
+/// use rs_isl::SocketTransport;
+///
+/// // started as `simulation <rank>` on one host
+/// let rank: usize = std::env::args().nth(1).unwrap().parse().unwrap();
+/// let addresses = vec!["127.0.0.1:7000".parse().unwrap(), "127.0.0.1:7001".parse().unwrap()];
+/// let transport = SocketTransport::tcp(rank, &addresses).unwrap();
+/// ```
+pub struct SocketTransport<S> {
+    rank: usize,
+    streams: Vec<Option<S>>,
+    max_message_len: usize,
+}
+
+impl<S> SocketTransport<S>
+where
+    S: Read + Write,
+{
+    /// Connects to all lower ranks with `connect` and accepts the connections of all higher ranks with `accept`
+    fn establish(
+        rank: usize,
+        size: usize,
+        mut connect: impl FnMut(usize) -> io::Result<S>,
+        mut accept: impl FnMut() -> io::Result<S>,
+    ) -> io::Result<Self> {
+        check_rank(rank, size)?;
+        let mut streams: Vec<Option<S>> = (0..size).map(|_| None).collect();
+
+        for (other, slot) in streams.iter_mut().enumerate().take(rank) {
+            let mut stream = connect_with_retry(|| connect(other))?;
+            stream.write_all(&(rank as u64).to_le_bytes())?;
+            *slot = Some(stream);
+        }
+        for _ in rank + 1..size {
+            let mut stream = accept()?;
+            let mut other = [0; 8];
+            stream.read_exact(&mut other)?;
+            let other = u64::from_le_bytes(other) as usize;
+            if other <= rank || other >= size || streams[other].is_some() {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unexpected connection from rank {other}"),
+                ));
+            }
+            streams[other] = Some(stream);
+        }
+
+        Ok(Self {
+            rank,
+            streams,
+            max_message_len: MAX_MESSAGE_LEN,
+        })
+    }
+
+    /// Rejects received messages longer than `len` bytes, 1 GiB by default
+    pub fn with_max_message_len(mut self, len: usize) -> Self {
+        self.max_message_len = len;
+        self
+    }
+
+    fn stream(&mut self, other: usize) -> &mut S {
+        self.streams[other]
+            .as_mut()
+            .unwrap_or_else(|| panic!("rank {} has no connection to rank {other}", self.rank))
+    }
+}
+
+impl SocketTransport<TcpStream> {
+    /// Listens on `addresses[rank]` and connects to the other ranks over TCP
+    pub fn tcp(rank: usize, addresses: &[SocketAddr]) -> io::Result<Self> {
+        check_rank(rank, addresses.len())?;
+        let listener = TcpListener::bind(addresses[rank])?;
+        Self::tcp_with_listener(rank, listener, addresses)
+    }
+
+    /// Like [SocketTransport::tcp], with a listener which is already bound to `addresses[rank]`
+    pub fn tcp_with_listener(
+        rank: usize,
+        listener: TcpListener,
+        addresses: &[SocketAddr],
+    ) -> io::Result<Self> {
+        Self::establish(
+            rank,
+            addresses.len(),
+            |other| {
+                let stream = TcpStream::connect(addresses[other])?;
+                stream.set_nodelay(true)?;
+                Ok(stream)
+            },
+            || {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(stream)
+            },
+        )
+    }
+}
+
+#[cfg(unix)]
+impl SocketTransport<UnixStream> {
+    /// Listens on the socket file `paths[rank]` and connects to the other ranks over Unix domain sockets.
+    ///
+    /// The socket files must not exist yet.
+    pub fn unix(rank: usize, paths: &[PathBuf]) -> io::Result<Self> {
+        check_rank(rank, paths.len())?;
+        let listener = UnixListener::bind(&paths[rank])?;
+        Self::establish(
+            rank,
+            paths.len(),
+            |other| UnixStream::connect(&paths[other]),
+            || listener.accept().map(|(stream, _)| stream),
+        )
+    }
+}
+
+impl<S> Transport for SocketTransport<S>
+where
+    S: Read + Write + Send,
+{
+    fn rank(&self) -> usize {
+        self.rank
+    }
+
+    fn size(&self) -> usize {
+        self.streams.len()
+    }
+
+    fn send(&mut self, to: usize, message: &[u8]) -> io::Result<()> {
+        let stream = self.stream(to);
+        stream.write_all(&(message.len() as u64).to_le_bytes())?;
+        stream.write_all(message)?;
+        stream.flush()
+    }
+
+    fn receive(&mut self, from: usize) -> io::Result<Vec<u8>> {
+        let max_message_len = self.max_message_len;
+        let stream = self.stream(from);
+        let mut len = [0; 8];
+        stream.read_exact(&mut len)?;
+        let len = u64::from_le_bytes(len);
+        if len > max_message_len as u64 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("message of {len} bytes from rank {from} exceeds the limit of {max_message_len} bytes"),
+            ));
+        }
+        let mut message = vec![0; len as usize];
+        stream.read_exact(&mut message)?;
+        Ok(message)
+    }
+}
+
+fn check_rank(rank: usize, size: usize) -> io::Result<()> {
+    if rank >= size {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("rank {rank} is not below the number of processes {size}"),
+        ));
+    }
+    Ok(())
+}
+
+fn connect_with_retry<S>(mut connect: impl FnMut() -> io::Result<S>) -> io::Result<S> {
+    let mut attempts = 1;
+    loop {
+        match connect() {
+            Err(error)
+                if attempts < CONNECT_ATTEMPTS
+                    && matches!(
+                        error.kind(),
+                        ErrorKind::ConnectionRefused | ErrorKind::NotFound
+                    ) =>
+            {
+                attempts += 1;
+                thread::sleep(CONNECT_INTERVAL);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Conversion of cell values to bytes and back, for sending them to other processes.
+///
+/// Implemented for the primitive number types and arrays of them.
+/// Decoding fails with [ErrorKind::InvalidData] if the bytes end in the middle of a value.
+///
+/// # Example
+/// ```rust, no_run
+/// use rs_isl::Encode;
+///
+/// #[derive(Clone)]
+/// struct Velocity {
+///     u: f32,
+///     v: f32,
+/// }
+///
+/// impl Encode for Velocity {
+///     fn encode(&self, out: &mut Vec<u8>) {
+///         self.u.encode(out);
+///         self.v.encode(out);
+///     }
+///
+///     fn decode(bytes: &mut &[u8]) -> std::io::Result<Self> {
+///         Ok(Velocity {
+///             u: f32::decode(bytes)?,
+///             v: f32::decode(bytes)?,
+///         })
+///     }
+/// }
+/// ```
+pub trait Encode: Sized {
+    /// Appends the bytes of the value
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads a value from the start of `bytes` and advances it past the value
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self>;
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        "the message ends in the middle of a value",
+    )
+}
+
+macro_rules! impl_encode {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+                    let (value, rest) = bytes
+                        .split_first_chunk::<{ size_of::<$t>() }>()
+                        .ok_or_else(truncated)?;
+                    *bytes = rest;
+                    Ok(<$t>::from_le_bytes(*value))
+                }
+            }
+        )*
+    };
+}
+
+impl_encode!(f32, f64, i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        Ok(u8::decode(bytes)? != 0)
+    }
+}
+
+impl<T, const N: usize> Encode for [T; N]
+where
+    T: Encode,
+{
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.iter().for_each(|value| value.encode(out));
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        let values = (0..N)
+            .map(|_| T::decode(bytes))
+            .collect::<io::Result<Vec<T>>>()?;
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("{N} values were decoded")))
+    }
+}
@@ -0,0 +1,85 @@
+use std::{ops::Deref, sync::Arc};
+
+use parking_lot::RwLock;
+
+/// Read access to the values of the grid.
+///
+/// Coordinates are given as (x, y), matching the `init` closure of [IslParams](crate::IslParams).
+pub struct GridView<'a, T> {
+    grid: &'a [Vec<Arc<RwLock<T>>>],
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub(crate) fn new(grid: &'a [Vec<Arc<RwLock<T>>>]) -> Self {
+        Self { grid }
+    }
+
+    /// The size of the grid, (x,y)
+    pub fn dimension(&self) -> (usize, usize) {
+        (self.grid[0].len(), self.grid.len())
+    }
+
+    /// Returns a copy of the value at (x,y)
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x,y) lies outside of the grid.
+    pub fn get(&self, x: usize, y: usize) -> T
+    where
+        T: Clone,
+    {
+        self.grid[y][x].read().clone()
+    }
+
+    /// Copies all values, indexed by `[x][y]`
+    pub fn to_vec(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        let (dim_x, dim_y) = self.dimension();
+        (0..dim_x)
+            .map(|x| (0..dim_y).map(|y| self.get(x, y)).collect())
+            .collect()
+    }
+}
+
+/// Read and write access to the values of the grid.
+///
+/// Dereferences to [GridView] for reading.
+pub struct GridViewMut<'a, T> {
+    view: GridView<'a, T>,
+}
+
+impl<'a, T> GridViewMut<'a, T> {
+    pub(crate) fn new(grid: &'a [Vec<Arc<RwLock<T>>>]) -> Self {
+        Self {
+            view: GridView::new(grid),
+        }
+    }
+
+    /// Overwrites the value at (x,y)
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x,y) lies outside of the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        *self.view.grid[y][x].write() = value;
+    }
+
+    /// Modifies the value at (x,y) in place
+    ///
+    /// # Panics
+    ///
+    /// Panics if (x,y) lies outside of the grid.
+    pub fn update(&mut self, x: usize, y: usize, f: impl FnOnce(&mut T)) {
+        f(&mut self.view.grid[y][x].write());
+    }
+}
+
+impl<'a, T> Deref for GridViewMut<'a, T> {
+    type Target = GridView<'a, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.view
+    }
+}
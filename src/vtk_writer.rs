@@ -1,13 +1,350 @@
-use std::{marker::PhantomData, path::PathBuf};
+use std::{
+    fs::{self, OpenOptions},
+    io::{Seek, SeekFrom, Write},
+    iter::repeat_n,
+    marker::PhantomData,
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
 use vtkio::{
     model::{
-        Attribute, Attributes, DataArray, DataSet, ElementType, Extent, StructuredGridPiece,
-        Version,
-    }, Vtk
+        Attribute, Attributes, DataArray, DataSet, ElementType, Extent, IOBuffer,
+        StructuredGridPiece, Version,
+    },
+    Vtk,
 };
 
-use crate::VtkOutput;
+use crate::{region::Region, VtkOutput};
+
+/// One value of a cell, written to the output files with the matching VTK data type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    F32(f32),
+    F64(f64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+}
+
+macro_rules! impl_value {
+    ($($t:ty, $variant:ident, $name:literal, $missing:expr;)*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::$variant(value)
+                }
+            }
+        )*
+
+        impl Value {
+            /// The zero of the same type
+            fn zero(&self) -> Value {
+                match self {
+                    $(Value::$variant(_) => Value::$variant(0 as $t),)*
+                }
+            }
+
+            /// The name of the type in XML files
+            fn type_name(&self) -> &'static str {
+                match self {
+                    $(Value::$variant(_) => $name,)*
+                }
+            }
+
+            /// Collects the values of an array with the type of `self`.
+            /// Missing values are written as NaN, or as the largest value of the type for integers.
+            fn collect(&self, data: Vec<Option<Value>>) -> IOBuffer {
+                match self {
+                    $(
+                        Value::$variant(_) => IOBuffer::$variant(
+                            data.into_iter()
+                                .map(|value| match value {
+                                    Some(Value::$variant(value)) => value,
+                                    None => $missing,
+                                    Some(_) => panic!("found irregular types of values when creating vtk output"),
+                                })
+                                .collect(),
+                        ),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+impl_value! {
+    f32, F32, "Float32", f32::NAN;
+    f64, F64, "Float64", f64::NAN;
+    i8, I8, "Int8", i8::MAX;
+    i16, I16, "Int16", i16::MAX;
+    i32, I32, "Int32", i32::MAX;
+    i64, I64, "Int64", i64::MAX;
+    u8, U8, "UInt8", u8::MAX;
+    u16, U16, "UInt16", u16::MAX;
+    u32, U32, "UInt32", u32::MAX;
+    u64, U64, "UInt64", u64::MAX;
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::U8(value as u8)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::U64(value as u64)
+    }
+}
+
+impl From<isize> for Value {
+    fn from(value: isize) -> Self {
+        Value::I64(value as i64)
+    }
+}
+
+/// How the values of a cell make up one output value, see [VtkOutput::value_kinds]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ValueKind {
+    /// A single value
+    #[default]
+    Scalar,
+    /// A vector of 2 or 3 components, (x,y) or (x,y,z). 2-dimensional vectors are written with a z of 0.
+    Vector(usize),
+    /// A 2x2 or 3x3 tensor, given row by row. 2-dimensional tensors are written as the upper left of a 3x3 tensor.
+    Tensor(usize),
+}
+
+impl ValueKind {
+    /// The number of values of a cell making up one output value of this kind
+    pub fn components(&self) -> usize {
+        match self {
+            ValueKind::Scalar => 1,
+            ValueKind::Vector(dim) => *dim,
+            ValueKind::Tensor(dim) => dim * dim,
+        }
+    }
+
+    /// The number of components written to the output files
+    fn written(&self) -> usize {
+        match self {
+            ValueKind::Scalar => 1,
+            ValueKind::Vector(_) => 3,
+            ValueKind::Tensor(_) => 9,
+        }
+    }
+
+    /// Appends `values`, padded with zeros to the written dimension
+    fn pad(&self, values: &[Value], out: &mut Vec<Option<Value>>) {
+        let zero = Some(values[0].zero());
+        match self {
+            ValueKind::Scalar => out.push(Some(values[0])),
+            ValueKind::Vector(_) => {
+                out.extend(values.iter().copied().map(Some));
+                out.extend(repeat_n(zero, 3 - values.len()));
+            }
+            ValueKind::Tensor(dim) => {
+                for row in values.chunks(*dim) {
+                    out.extend(row.iter().copied().map(Some));
+                    out.extend(repeat_n(zero, 3 - dim));
+                }
+                out.extend(repeat_n(zero, 3 * (3 - dim)));
+            }
+        }
+    }
+
+    fn element(&self) -> ElementType {
+        match self {
+            ValueKind::Scalar => ElementType::Scalars {
+                num_comp: 1,
+                lookup_table: None,
+            },
+            ValueKind::Vector(_) => ElementType::Vectors,
+            ValueKind::Tensor(_) => ElementType::Tensors,
+        }
+    }
+}
+
+/// Where the values of a cell are placed in the output files
+///
+/// # Example
+/// ```rust, no_run
+/// use std::path::PathBuf;
+///
+/// use rs_isl::*;
+///
+/// // finite volumes, every value is the average over its cell
+/// let average = |num: &f64, nb: Vec<Option<&f64>>| (num + nb.iter().map(|n| n.unwrap_or(num)).sum::<f64>()) / 3.0;
+/// let params = IslParams::new((200, 1), average, 2, |x, _| (x / 100) as f64, 50, 5, vec![(-1, 0), (1, 0)], PathBuf::from("raw"))
+///     .with_centring(Centring::Cell);
+/// run_isl(params).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Centring {
+    /// Point data, the value of cell (x,y) is placed at the point (x,y) and interpolated between points
+    #[default]
+    Point,
+    /// Cell data on a lattice of one point more along each axis.
+    /// Cell (x,y) spans from (x-0.5,y-0.5) to (x+0.5,y+0.5) and has a constant value.
+    ///
+    /// Cell data is written in the XML format, i.e. to `.vts` instead of `.vtk` files,
+    /// as the legacy writer of vtkio does not support it for structured grids.
+    Cell,
+}
+
+impl Centring {
+    /// The indices of the points of the grid holding `cells`
+    fn points(&self, cells: &Region) -> Region {
+        match self {
+            Centring::Point => cells.clone(),
+            Centring::Cell => Region {
+                x: cells.x.start..cells.x.end + 1,
+                y: cells.y.start..cells.y.end + 1,
+            },
+        }
+    }
+
+    /// The positions of the points of the grid holding `cells`, row by row with x varying fastest
+    fn positions(&self, cells: &Region) -> Vec<f32> {
+        let offset = match self {
+            Centring::Point => 0.0,
+            Centring::Cell => -0.5,
+        };
+        let points = self.points(cells);
+        let mut positions = Vec::with_capacity(points.len() * 3);
+        for y in points.y {
+            for x in points.x.clone() {
+                positions.extend([x as f32 + offset, y as f32 + offset, 0.0]);
+            }
+        }
+        positions
+    }
+
+    fn attributes(&self, arrays: Vec<Array>) -> Attributes {
+        let data = arrays.into_iter().map(Array::attribute).collect();
+        match self {
+            Centring::Point => Attributes {
+                point: data,
+                cell: vec![],
+            },
+            Centring::Cell => Attributes {
+                point: vec![],
+                cell: data,
+            },
+        }
+    }
+
+    /// The extension of the files holding the whole domain
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            Centring::Point => "vtk",
+            Centring::Cell => "vts",
+        }
+    }
+
+    /// The name of the data section in parallel XML files
+    fn parallel_section(&self) -> &'static str {
+        match self {
+            Centring::Point => "PPointData",
+            Centring::Cell => "PCellData",
+        }
+    }
+}
+
+/// One output value of every cell, as written to the output files
+pub(crate) struct Array {
+    pub(crate) name: String,
+    pub(crate) kind: ValueKind,
+    /// The components of every cell, `None` for missing cells
+    data: Vec<Option<Value>>,
+}
+
+impl Array {
+    /// The number of components of every element, as written to the output files
+    pub(crate) fn num_comp(&self) -> usize {
+        self.kind.written()
+    }
+
+    /// The name of the data type in XML files
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.first().type_name()
+    }
+
+    /// The first value, which determines the data type of the array. Arrays without values are written as f32.
+    fn first(&self) -> Value {
+        self.data
+            .iter()
+            .flatten()
+            .next()
+            .copied()
+            .unwrap_or(Value::F32(f32::NAN))
+    }
+
+    fn attribute(self) -> Attribute {
+        let first = self.first();
+        Attribute::DataArray(DataArray {
+            name: self.name,
+            elem: self.kind.element(),
+            data: first.collect(self.data),
+        })
+    }
+}
+
+/// Empty arrays for the output values of `T`, with room for `capacity` cells
+///
+/// # Panics
+///
+/// Panics if the names and kinds of the values don't match or if a kind has a dimension other than 2 or 3.
+pub(crate) fn arrays<T: VtkOutput>(capacity: usize) -> Vec<Array> {
+    let names = T::value_names();
+    let kinds = T::value_kinds();
+    if names.len() != kinds.len() {
+        panic!("found a different number of value names and value kinds when creating vtk output");
+    }
+    names
+        .into_iter()
+        .zip(kinds)
+        .map(|(name, kind)| {
+            if let ValueKind::Vector(dim) | ValueKind::Tensor(dim) = kind {
+                assert!(
+                    (2..=3).contains(&dim),
+                    "vectors and tensors must have 2 or 3 dimensions"
+                );
+            }
+            Array {
+                name,
+                kind,
+                data: Vec::with_capacity(capacity * kind.written()),
+            }
+        })
+        .collect()
+}
+
+/// Appends the values of one cell to `arrays`, missing values are written as NaN, or as the largest value of the type for integers
+pub(crate) fn push_values<T: VtkOutput>(arrays: &mut [Array], value: Option<&T>) {
+    let Some(value) = value else {
+        for array in arrays {
+            array.data.extend(repeat_n(None, array.kind.written()));
+        }
+        return;
+    };
+    let values = value.cellvalue();
+    if values.len() != arrays.iter().map(|array| array.kind.components()).sum() {
+        panic!("found irregular length of values when creating vtk output");
+    }
+    let mut values = values.as_slice();
+    for array in arrays {
+        let (value, rest) = values.split_at(array.kind.components());
+        array.kind.pad(value, &mut array.data);
+        values = rest;
+    }
+}
 
 pub struct VtkWriter<T>
 where
@@ -15,74 +352,185 @@ where
 {
     idx: usize,
     path: PathBuf,
+    centring: Centring,
     r_type: PhantomData<T>,
-    rows: Vec<String>,
 }
 
 impl<T> VtkWriter<T>
 where
     T: VtkOutput,
 {
-    pub fn new(path: PathBuf, rows: Vec<String>) -> Self {
+    /// # Panics
+    ///
+    /// Panics if the names and kinds of the output values of `T` don't match
+    pub fn new(path: PathBuf, centring: Centring) -> Self {
+        arrays::<T>(0);
         Self {
             idx: 0,
             path,
+            centring,
             r_type: PhantomData,
-            rows,
         }
     }
 
-    pub fn write_step(&mut self, data: Vec<Vec<T>>) {
-        let num_values = self.rows.len();
+    /// Writes one snapshot, missing values are written as NaN, or as the largest value of the type for integers
+    pub fn write_step(&mut self, data: Vec<Vec<Option<T>>>, time: f64) {
         let dim = (data.len() as u32, data[0].len() as u32);
-        let mut points = Vec::with_capacity((dim.0 * dim.1) as usize);
-        let mut out_data = vec![Vec::with_capacity((dim.0 * dim.1) as usize); num_values];
-
-        for x in 0..dim.0 {
-            for y in 0..dim.1 {
-                points.append(&mut vec![y as f32, x as f32, 0f32]);
-                let cv = data[x as usize][y as usize].cellvalue();
-                if cv.len() != self.rows.len() {
-                    panic!("found irregular length of values when creating vtk output");
-                }
-                for i in 0..num_values {
-                    out_data[i].push(cv[i]);
-                }
+        let mut arrays = arrays::<T>((dim.0 * dim.1) as usize);
+
+        for row in &data {
+            for value in row {
+                push_values(&mut arrays, value.as_ref());
             }
         }
 
-        let mut point_data = vec![];
-
-        for (idx, line) in out_data.into_iter().enumerate() {
-            point_data.push(Attribute::DataArray(DataArray {
-                name: self.rows[idx].clone(),
-                elem: ElementType::Scalars {
-                    num_comp: 1,
-                    lookup_table: None,
-                },
-                data: line.into(),
-            }));
-        }
+        write_arrays(&self.path, self.idx, dim, arrays, self.centring);
+        write_collection(&self.path, self.idx, time, self.centring.extension());
+        self.idx += 1;
+    }
+}
 
-        let out = Vtk {
-            version: Version::new((1, 0)),
-            byte_order: vtkio::model::ByteOrder::BigEndian,
-            title: String::from("rs_isl_Output"),
-            file_path: None,
-            data: DataSet::inline(StructuredGridPiece {
-                extent: Extent::Dims([dim.1, dim.0, 1]),
-                points: points.into(),
-                data: Attributes {
-                    point: point_data,
-                    cell: vec![],
-                },
-            }),
-        };
+/// Writes arrays of point data into `ISL{idx}.vtk`, or of cell data into `ISL{idx}.vts`, see [Centring::Cell].
+///
+/// `dim` is (y,x) and every array holds the values row by row, with x varying fastest.
+pub(crate) fn write_arrays(
+    path: &Path,
+    idx: usize,
+    dim: (u32, u32),
+    arrays: Vec<Array>,
+    centring: Centring,
+) {
+    let cells = Region {
+        x: 0..dim.1 as usize,
+        y: 0..dim.0 as usize,
+    };
+    if centring == Centring::Cell {
+        write_xml(
+            &path.join(format!("ISL{idx:?}.vts")),
+            &cells,
+            arrays,
+            centring,
+        );
+        return;
+    }
 
-        let mut out_path = self.path.clone();
-        out_path.push(format!("ISL{:?}.vtk", self.idx));
+    let out = Vtk {
+        version: Version::new((1, 0)),
+        byte_order: vtkio::model::ByteOrder::BigEndian,
+        title: String::from("rs_isl_Output"),
+        file_path: None,
+        data: DataSet::inline(StructuredGridPiece {
+            extent: Extent::Dims([dim.1, dim.0, 1]),
+            points: centring.positions(&cells).into(),
+            data: centring.attributes(arrays),
+        }),
+    };
 
-        out.export(out_path).unwrap();
-        self.idx += 1;
+    let mut out_path = path.to_path_buf();
+    out_path.push(format!("ISL{:?}.vtk", idx));
+
+    out.export(out_path).unwrap();
+}
+
+const COLLECTION_FOOTER: &str = "  </Collection>\n</VTKFile>\n";
+
+/// Adds the file `ISL{idx}` with its simulated time to `ISL.pvd`, which lists the files written so far.
+///
+/// Loading this file instead of the single `.vtk` files lets ParaView show the actual time of each snapshot.
+/// `extension` is the one of the listed files, e.g. `vtk`. The file is created for `idx` 0,
+/// later entries overwrite the closing tags and append them again instead of rewriting the whole file.
+pub(crate) fn write_collection(path: &Path, idx: usize, time: f64, extension: &str) {
+    let entry = format!(
+        "    <DataSet timestep=\"{time:?}\" file=\"ISL{idx:?}.{extension}\"/>\n{COLLECTION_FOOTER}"
+    );
+    let path = path.join("ISL.pvd");
+    if idx == 0 {
+        let header =
+            "<?xml version=\"1.0\"?>\n<VTKFile type=\"Collection\" version=\"0.1\">\n  <Collection>\n";
+        fs::write(path, header.to_string() + &entry).unwrap();
+        return;
+    }
+
+    let mut file = OpenOptions::new().write(true).open(path).unwrap();
+    file.seek(SeekFrom::End(-(COLLECTION_FOOTER.len() as i64)))
+        .unwrap();
+    file.write_all(entry.as_bytes()).unwrap();
+}
+
+/// Writes the part of the domain given by `region` into `ISL{idx}_{rank}.vts`, in the XML format.
+pub(crate) fn write_piece(
+    path: &Path,
+    idx: usize,
+    rank: usize,
+    region: &Region,
+    arrays: Vec<Array>,
+    centring: Centring,
+) {
+    let file = path.join(format!("ISL{idx:?}_{rank:?}.vts"));
+    write_xml(&file, region, arrays, centring);
+}
+
+/// Writes the cells given by `region` into `file`, in the XML format.
+///
+/// The points keep their position in the whole domain and every array holds the values row by row,
+/// with x varying fastest.
+fn write_xml(file: &Path, region: &Region, arrays: Vec<Array>, centring: Centring) {
+    let points = centring.points(region);
+    let extent = |range: &Range<usize>| range.start as i32..=range.end as i32 - 1;
+    let out = Vtk {
+        version: Version::new((1, 0)),
+        byte_order: vtkio::model::ByteOrder::LittleEndian,
+        title: String::from("rs_isl_Output"),
+        file_path: None,
+        data: DataSet::inline(StructuredGridPiece {
+            extent: Extent::Ranges([extent(&points.x), extent(&points.y), 0..=0]),
+            points: centring.positions(region).into(),
+            data: centring.attributes(arrays),
+        }),
+    };
+
+    out.export(file).unwrap();
+}
+
+/// Writes `ISL{idx}.pvts`, which combines the pieces written by every rank with [write_piece].
+///
+/// `pieces` holds the region written by each rank, `dimension` is the size of the whole domain, (x,y).
+/// `columns` holds the name, data type and number of components of every array.
+pub(crate) fn write_parallel(
+    path: &Path,
+    idx: usize,
+    dimension: (usize, usize),
+    pieces: &[Region],
+    columns: &[(String, &str, usize)],
+    centring: Centring,
+) {
+    let whole = centring.points(&Region {
+        x: 0..dimension.0,
+        y: 0..dimension.1,
+    });
+    let section = centring.parallel_section();
+    let mut file = format!(
+        "<?xml version=\"1.0\"?>\n<VTKFile type=\"PStructuredGrid\" version=\"0.1\" byte_order=\"LittleEndian\">\n  <PStructuredGrid WholeExtent=\"0 {} 0 {} 0 0\" GhostLevel=\"0\">\n    <{section}>\n",
+        whole.x.end - 1,
+        whole.y.end - 1
+    );
+    for (name, data_type, components) in columns {
+        file.push_str(&format!(
+            "      <PDataArray type=\"{data_type}\" Name=\"{name}\" NumberOfComponents=\"{components}\"/>\n"
+        ));
+    }
+    file.push_str(&format!("    </{section}>\n    <PPoints>\n      <PDataArray type=\"Float32\" NumberOfComponents=\"3\"/>\n    </PPoints>\n"));
+    for (rank, region) in pieces.iter().enumerate() {
+        let points = centring.points(region);
+        file.push_str(&format!(
+            "    <Piece Extent=\"{} {} {} {} 0 0\" Source=\"ISL{idx:?}_{rank:?}.vts\"/>\n",
+            points.x.start,
+            points.x.end - 1,
+            points.y.start,
+            points.y.end - 1
+        ));
     }
+    file.push_str("  </PStructuredGrid>\n</VTKFile>\n");
+
+    fs::write(path.join(format!("ISL{idx:?}.pvts")), file).unwrap();
 }
@@ -1,5 +1,12 @@
 use std::marker::PhantomData;
 
+use crate::{
+    grid::IslError,
+    operator::{Context, Operator},
+    region::Region,
+    view::GridView,
+};
+
 #[derive(Clone)]
 pub struct WithCall<F, T>
 where
@@ -12,7 +19,7 @@ where
 
 impl<F, T> WithCall<F, T>
 where
-    F: Fn(&T, Vec<Option<&T>>) -> T,
+    F: Operator<T>,
     T: Clone,
 {
     pub fn new(fp: F) -> Self {
@@ -22,7 +29,41 @@ where
         }
     }
 
-    pub fn run(&self, a: &T, b: Vec<Option<&T>>) -> T {
-        (self.fp)(a, b)
+    pub fn run(&self, c: &Context, a: &T, b: Vec<Option<&T>>) -> T {
+        self.fp.apply(c, a, b)
+    }
+
+    pub fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        self.fp.offsets()
+    }
+
+    pub fn check(&self, dimension: (usize, usize)) -> Result<(), IslError> {
+        self.fp.check(dimension)
+    }
+
+    pub fn run_region(&self, grid: &GridView<'_, T>, region: &Region) -> Option<Vec<T>> {
+        self.fp.apply_region(grid, region)
+    }
+}
+
+impl<F, T> Operator<T> for WithCall<F, T>
+where
+    F: Operator<T>,
+    T: Clone + Send + Sync,
+{
+    fn apply(&self, context: &Context, value: &T, neighbours: Vec<Option<&T>>) -> T {
+        self.run(context, value, neighbours)
+    }
+
+    fn offsets(&self) -> Option<Vec<(i8, i8)>> {
+        self.fp.offsets()
+    }
+
+    fn check(&self, dimension: (usize, usize)) -> Result<(), IslError> {
+        self.fp.check(dimension)
+    }
+
+    fn apply_region(&self, grid: &GridView<'_, T>, region: &Region) -> Option<Vec<T>> {
+        self.fp.apply_region(grid, region)
     }
 }
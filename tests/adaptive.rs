@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use rs_isl::{run_isl, AdaptiveDt, Context, Contextual, Integrator, IslParams, Simulation};
+
+    fn halving() -> AdaptiveDt<f32> {
+        AdaptiveDt::new(|value: &f32| *value as f64, |max| 1.0 / max)
+    }
+
+    #[test]
+    fn dt_follows_the_global_maximum() {
+        // one cell doubles every step, all others stay the same
+        let op = Contextual(|context: &Context, num: &f32, _nb: Vec<Option<&f32>>| {
+            if context.x == 7 && context.y == 3 {
+                num * 2.0
+            } else {
+                *num
+            }
+        });
+        let params = IslParams::new(
+            (8, 6),
+            op,
+            4,
+            |_, _| 1.0f32,
+            0,
+            0,
+            vec![],
+            PathBuf::from("raw"),
+        )
+        .with_adaptive_dt(halving());
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step();
+        assert_eq!((sim.dt(), sim.time()), (1.0, 1.0));
+        sim.step();
+        assert_eq!((sim.dt(), sim.time()), (0.5, 1.5));
+        sim.step();
+        assert_eq!((sim.dt(), sim.time()), (0.25, 1.75));
+    }
+
+    #[test]
+    fn operators_see_dt_and_time() {
+        let run = |runners: usize| {
+            // the value tracks the simulated time, through dt as well as time
+            let op = Contextual(|context: &Context, num: &f32, _nb: Vec<Option<&f32>>| {
+                assert!((*num as f64 - context.time).abs() < 1e-5);
+                num + context.dt as f32
+            });
+            let params = IslParams::new(
+                (6, 4),
+                op,
+                runners,
+                |_, _| 0.0f32,
+                0,
+                0,
+                vec![],
+                PathBuf::from("raw"),
+            )
+            .with_adaptive_dt(AdaptiveDt::new(
+                |value: &f32| *value as f64,
+                |max| 1.0 / (1.0 + max),
+            ));
+
+            let mut sim = Simulation::new(params).unwrap();
+            sim.step_n(6);
+            assert!((sim.view().get(5, 3) as f64 - sim.time()).abs() < 1e-5);
+            sim.view().to_vec()
+        };
+
+        assert_eq!(run(1), run(4));
+    }
+
+    #[test]
+    fn stages_see_their_time() {
+        // du/dt = t, which RK4 integrates exactly
+        let rhs =
+            Contextual(|context: &Context, _num: &f32, _nb: Vec<Option<&f32>>| context.time as f32);
+        let params = IslParams::new(
+            (4, 4),
+            rhs,
+            2,
+            |_, _| 0.0f32,
+            0,
+            0,
+            vec![],
+            PathBuf::from("raw"),
+        )
+        .with_integrator(Integrator::Rk4)
+        .with_dt(0.25);
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(8);
+        assert_eq!(sim.time(), 2.0);
+        assert!((sim.view().get(1, 1) - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn time_is_written_with_every_snapshot() {
+        let path = env::temp_dir().join("rs_isl_adaptive_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let op = |num: &f32, _nb: Vec<Option<&f32>>| num * 2.0;
+        let params = IslParams::new((4, 4), op, 1, |_, _| 1.0f32, 4, 4, vec![], path.clone())
+            .with_adaptive_dt(halving());
+        run_isl(params).unwrap();
+
+        let collection = fs::read_to_string(path.join("ISL.pvd")).unwrap();
+        assert!(collection.contains("timestep=\"1.0\" file=\"ISL0.vtk\""));
+        assert!(collection.contains("timestep=\"1.875\" file=\"ISL3.vtk\""));
+        // entries are appended before the closing tags, which appear once
+        assert_eq!(collection.matches("<DataSet ").count(), 4);
+        assert_eq!(collection.matches("</Collection>").count(), 1);
+        assert!(collection.ends_with("  </Collection>\n</VTKFile>\n"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn dt_must_be_positive() {
+        let op = |num: &f32, _nb: Vec<Option<&f32>>| *num;
+        let params = IslParams::new(
+            (4, 4),
+            op,
+            1,
+            |_, _| 0.0f32,
+            0,
+            0,
+            vec![],
+            PathBuf::from("raw"),
+        )
+        .with_adaptive_dt(halving());
+        Simulation::new(params).unwrap().step();
+    }
+}
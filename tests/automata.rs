@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{
+        count, Context, Contextual, IslParams, Majority, Operator, Simulation, Threshold,
+        UpdateOrder, WithProbability,
+    };
+
+    const DIM: (usize, usize) = (40, 30);
+    const VON_NEUMANN: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    fn run<F>(op: F, runners: usize, init: fn(usize, usize) -> u8, steps: usize) -> Vec<Vec<u8>>
+    where
+        F: Operator<u8> + Clone + 'static,
+    {
+        let params = IslParams::new(
+            DIM,
+            op,
+            runners,
+            init,
+            0,
+            0,
+            VON_NEUMANN.to_vec(),
+            PathBuf::from("raw"),
+        )
+        .with_seed(11);
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(steps);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn counts_existing_matching_neighbours() {
+        let (a, b) = (1, 2);
+        let neighbours = vec![Some(&a), None, Some(&b), Some(&a)];
+        assert_eq!(count(&neighbours, |value| *value == 1), 2);
+        assert_eq!(count(&neighbours, |_| true), 3);
+    }
+
+    #[test]
+    fn probability_decides_per_cell() {
+        let set = |_: &u8, _: Vec<Option<&u8>>| 1;
+        let never = run(WithProbability::new(0.0, set), 4, |_, _| 0, 1);
+        assert!(never.iter().flatten().all(|value| *value == 0));
+        let always = run(WithProbability::new(1.0, set), 4, |_, _| 0, 1);
+        assert!(always.iter().flatten().all(|value| *value == 1));
+
+        let some = run(WithProbability::new(0.3, set), 4, |_, _| 0, 1);
+        let share = some.iter().flatten().filter(|value| **value == 1).count() as f64
+            / (DIM.0 * DIM.1) as f64;
+        assert!((share - 0.3).abs() < 0.05, "{share}");
+        assert_eq!(run(WithProbability::new(0.3, set), 1, |_, _| 0, 1), some);
+    }
+
+    #[test]
+    fn wrapped_operator_draws_independent_numbers() {
+        // the inner draw would always be below 0.5 if it shared the numbers of the outer one
+        let draw = Contextual(|context: &Context, _: &u8, _: Vec<Option<&u8>>| {
+            (context.rng().uniform() * 2.0) as u8 + 1
+        });
+        let values = run(WithProbability::new(0.5, draw), 4, |_, _| 0, 1);
+        assert!(values.iter().flatten().any(|value| *value == 1));
+        assert!(values.iter().flatten().any(|value| *value == 2));
+    }
+
+    #[test]
+    fn threshold_spreads_into_corners() {
+        // seeds on the diagonal fill the whole square they span, but nothing beyond
+        let diagonal = |x: usize, y: usize| (x == y && (5..10).contains(&x)) as u8;
+        let filled = run(Threshold::new(1, 2), 4, diagonal, 10);
+        for (x, column) in filled.iter().enumerate() {
+            for (y, value) in column.iter().enumerate() {
+                let inside = (5..10).contains(&x) && (5..10).contains(&y);
+                assert_eq!(*value, inside as u8, "({x},{y})");
+            }
+        }
+    }
+
+    #[test]
+    fn majority_smooths_noise() {
+        // a single differing cell is outvoted, a tie keeps the cell's value
+        let init = |x: usize, y: usize| ((x, y) == (10, 10) || x >= 20) as u8;
+        let smoothed = run(Majority, 2, init, 1);
+        assert_eq!(smoothed[10][10], 0);
+        assert_eq!(smoothed[19][10], 0);
+        assert_eq!(smoothed[20][10], 1);
+    }
+
+    #[test]
+    fn checkerboard_metropolis_orders_at_low_temperature() {
+        // spins 0 and 1 stand for -1 and +1
+        let spin = |value: &u8| *value as i32 * 2 - 1;
+        let acceptance = move |value: &u8, nb: &[Option<&u8>]| {
+            let field: i32 = nb.iter().flatten().map(|nb| spin(nb)).sum();
+            (-2.0 * (spin(value) * field) as f64 / 0.5).exp().min(1.0)
+        };
+        let metropolis =
+            WithProbability::varying(acceptance, |value: &u8, _: Vec<Option<&u8>>| 1 - value);
+        let energy = |spins: &Vec<Vec<u8>>| {
+            let mut energy = 0;
+            for x in 0..DIM.0 {
+                for y in 0..DIM.1 {
+                    if x + 1 < DIM.0 {
+                        energy -= spin(&spins[x][y]) * spin(&spins[x + 1][y]);
+                    }
+                    if y + 1 < DIM.1 {
+                        energy -= spin(&spins[x][y]) * spin(&spins[x][y + 1]);
+                    }
+                }
+            }
+            energy
+        };
+
+        let run = |runners| {
+            let params = IslParams::new(
+                DIM,
+                metropolis.clone(),
+                runners,
+                |x, y| ((x * 7 + y * 11) % 5 < 2) as u8,
+                0,
+                0,
+                VON_NEUMANN.to_vec(),
+                PathBuf::from("raw"),
+            )
+            .with_update_order(UpdateOrder::RedBlack)
+            .with_seed(5);
+            let initial = Simulation::new(params).unwrap();
+            let before = initial.view().to_vec();
+            let mut sim = initial;
+            sim.step_n(30);
+            (before, sim.view().to_vec())
+        };
+
+        let (before, after) = run(4);
+        assert!(energy(&after) < energy(&before) / 2);
+        assert_eq!(run(1).1, after);
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+        thread::{self, ThreadId},
+        time::Duration,
+    };
+
+    use rs_isl::{Context, Contextual, IslParams, Simulation, UpdateOrder};
+
+    const DIM: (usize, usize) = (24, 8);
+    const NEIGHBOURS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    type Op = fn(&f32, Vec<Option<&f32>>) -> f32;
+    type Init = fn(usize, usize) -> f32;
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 5 + y * 3) % 7) as f32
+    }
+
+    fn average(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let sum: f32 = nb.iter().flatten().copied().sum();
+        (num + sum) / (1 + nb.iter().flatten().count()) as f32
+    }
+
+    fn params(runners: usize) -> IslParams<f32, Op, Init> {
+        IslParams::new(
+            DIM,
+            average as Op,
+            runners,
+            init as Init,
+            0,
+            0,
+            NEIGHBOURS.to_vec(),
+            PathBuf::from("raw"),
+        )
+    }
+
+    fn run(params: IslParams<f32, Op, Init>) -> Vec<Vec<f32>> {
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(5);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn partitioning_keeps_results() {
+        let expected = run(params(1));
+        let cost = |x: usize, y: usize| if x < 6 && y < 2 { 10.0 } else { 1.0 };
+
+        assert_eq!(run(params(4).with_cost(cost)), expected);
+        assert_eq!(run(params(4).with_rebalancing(2)), expected);
+        assert_eq!(run(params(6).with_cost(cost).with_rebalancing(0)), expected);
+    }
+
+    #[test]
+    fn rebalancing_keeps_colours_and_masks() {
+        let active = |x: usize, y: usize| x % 5 != 2 || y == 4;
+        let params = |runners| {
+            params(runners)
+                .with_update_order(UpdateOrder::RedBlack)
+                .with_mask(active)
+        };
+
+        assert_eq!(run(params(4).with_rebalancing(1)), run(params(1)));
+    }
+
+    #[test]
+    fn expensive_cells_are_shared_by_more_runners() {
+        // cells counted per runner after the domain was repartitioned
+        let counts: Arc<Mutex<HashMap<ThreadId, usize>>> = Arc::default();
+        let op = {
+            let counts = counts.clone();
+            Contextual(move |context: &Context, num: &f32, nb: Vec<Option<&f32>>| {
+                if context.x < 6 {
+                    thread::sleep(Duration::from_micros(200));
+                }
+                if context.step == 2 {
+                    *counts
+                        .lock()
+                        .unwrap()
+                        .entry(thread::current().id())
+                        .or_default() += 1;
+                }
+                average(num, nb)
+            })
+        };
+        let params = IslParams::new(
+            DIM,
+            op,
+            4,
+            init,
+            0,
+            0,
+            NEIGHBOURS.to_vec(),
+            PathBuf::from("raw"),
+        )
+        .with_rebalancing(2);
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(3);
+
+        // equal blocks would hold 48 cells each
+        let counts = counts.lock().unwrap();
+        assert_eq!(counts.values().sum::<usize>(), DIM.0 * DIM.1);
+        assert!(counts.values().min().unwrap() < &48);
+        assert!(counts.values().max().unwrap() > &48);
+    }
+}
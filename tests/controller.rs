@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, thread};
+
+    use rs_isl::{run_isl, Controller, IslParams};
+
+    #[test]
+    fn pause_modify_and_resume() {
+        let path = env::temp_dir().join("rs_isl_controller_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let controller = Controller::new();
+
+        // every cell counts the number of steps
+        let op = |num: &f32, _nb: Vec<Option<&f32>>| *num + 1.0;
+        let init = |_x: usize, _y: usize| 0.0;
+
+        let params = IslParams::new((20, 10), op, 4, init, 500, 1, vec![(1, 0)], path)
+            .with_controller(controller.clone());
+
+        let handle = thread::spawn(move || run_isl(params));
+
+        let mut grid = controller.pause().unwrap();
+        let first = grid.step();
+        assert_eq!(grid.dimension(), (20, 10));
+        assert_eq!(grid.get(3, 7), first as f32);
+        grid.set(3, 7, 1000.0);
+        grid.resume();
+
+        if let Some(grid) = controller.pause() {
+            let second = grid.step();
+            assert!(second > first);
+            assert_eq!(grid.get(3, 7), 1000.0 + (second - first) as f32);
+            assert_eq!(grid.get(4, 7), second as f32);
+        }
+
+        assert!(handle.join().unwrap().is_ok());
+        assert!(controller.is_finished());
+        assert!(controller.pause().is_none());
+    }
+
+    #[test]
+    fn panicking_operator_finishes_the_controller() {
+        let path = env::temp_dir().join("rs_isl_controller_panic_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let controller = Controller::new();
+
+        let op = |num: &f32, _nb: Vec<Option<&f32>>| {
+            if *num >= 3.0 {
+                panic!("operator failed");
+            }
+            *num + 1.0
+        };
+        let init = |_x: usize, _y: usize| 0.0;
+
+        let params = IslParams::new((20, 10), op, 4, init, 500, 1, vec![], path)
+            .with_controller(controller.clone());
+
+        let handle = thread::spawn(move || run_isl(params));
+
+        // pausing must not block forever once the runners stopped
+        while controller.pause().is_some() {}
+
+        assert!(handle.join().is_err());
+        assert!(controller.is_finished());
+    }
+}
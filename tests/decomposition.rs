@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{Decomposition, IslParams, Region, Simulation, ThreadExecutor};
+
+    fn covers(dimension: (usize, usize), regions: &[Region]) -> bool {
+        (0..dimension.0).all(|x| {
+            (0..dimension.1).all(|y| regions.iter().filter(|r| r.contains(x, y)).count() == 1)
+        })
+    }
+
+    #[test]
+    fn every_cell_belongs_to_one_block() {
+        let decompositions = [
+            Decomposition::Square,
+            Decomposition::Auto,
+            Decomposition::Rows,
+            Decomposition::Columns,
+            Decomposition::Blocks(2, 3),
+        ];
+        for decomposition in decompositions {
+            for dimension in [(9, 6), (12, 10), (30, 7)] {
+                let regions = Region::decompose(dimension, 6, decomposition).unwrap();
+                assert_eq!(regions.len(), 6);
+                assert!(
+                    covers(dimension, &regions),
+                    "{decomposition:?} {dimension:?}"
+                );
+            }
+        }
+
+        // the x axis is not divisible by the two blocks along it
+        let regions = Region::runner_blocks((9, 4), 4).unwrap();
+        assert!(covers((9, 4), &regions));
+    }
+
+    #[test]
+    fn strips_and_blocks() {
+        let rows = Region::decompose((40, 12), 4, Decomposition::Rows).unwrap();
+        assert!(rows
+            .iter()
+            .all(|region| region.x == (0..40) && region.y.len() == 3));
+
+        let columns = Region::decompose((40, 12), 4, Decomposition::Columns).unwrap();
+        assert!(columns
+            .iter()
+            .all(|region| region.y == (0..12) && region.x.len() == 10));
+
+        let blocks = Region::decompose((40, 12), 8, Decomposition::Blocks(4, 2)).unwrap();
+        assert!(blocks
+            .iter()
+            .all(|region| region.x.len() == 10 && region.y.len() == 6));
+
+        // 10 rows are shared by 4 strips
+        let uneven = Region::decompose((40, 10), 4, Decomposition::Rows).unwrap();
+        let heights: Vec<usize> = uneven.iter().map(|region| region.y.len()).collect();
+        assert_eq!(heights, vec![2, 3, 2, 3]);
+
+        assert!(Region::decompose((40, 12), 8, Decomposition::Blocks(3, 2)).is_err());
+        assert!(Region::decompose((40, 2), 4, Decomposition::Rows).is_err());
+    }
+
+    #[test]
+    fn auto_follows_the_aspect_ratio() {
+        let wide = Region::decompose((200, 10), 8, Decomposition::Auto).unwrap();
+        assert_eq!(
+            wide,
+            Region::decompose((200, 10), 8, Decomposition::Columns).unwrap()
+        );
+
+        let tall = Region::decompose((10, 200), 8, Decomposition::Auto).unwrap();
+        assert_eq!(
+            tall,
+            Region::decompose((10, 200), 8, Decomposition::Rows).unwrap()
+        );
+
+        let square = Region::decompose((40, 40), 4, Decomposition::Auto).unwrap();
+        assert!(square
+            .iter()
+            .all(|region| region.len() == 400 && region.x.len() == 20));
+    }
+
+    #[test]
+    fn simulation_exposes_its_partition() {
+        let op = |num: &f32, nb: Vec<Option<&f32>>| num + nb.iter().flatten().copied().sum::<f32>();
+        let params = |decomposition| {
+            IslParams::new(
+                (24, 8),
+                op,
+                4,
+                |x, y| (x + y) as f32,
+                0,
+                0,
+                vec![(-1, 0), (0, 1)],
+                PathBuf::from("raw"),
+            )
+            .with_decomposition(decomposition)
+        };
+
+        let mut expected = Simulation::new(params(Decomposition::Square)).unwrap();
+        expected.step_n(3);
+
+        let mut sim = Simulation::new(params(Decomposition::Rows)).unwrap();
+        sim.step_n(3);
+        assert_eq!(sim.view().to_vec(), expected.view().to_vec());
+        assert_eq!(
+            sim.regions(),
+            Region::decompose((24, 8), 4, Decomposition::Rows).unwrap()
+        );
+
+        // an executor brings its own decomposition
+        let executor = ThreadExecutor::new(4).with_decomposition(Decomposition::Columns);
+        let sim = Simulation::new(params(Decomposition::Rows).with_executor(executor)).unwrap();
+        assert!(sim.regions().iter().all(|region| region.y == (0..8)));
+    }
+
+    #[test]
+    fn blocks_need_not_divide_the_domain() {
+        // 70 cells can not be shared evenly by 4 runners
+        let regions = Region::decompose((10, 7), 4, Decomposition::Square).unwrap();
+        assert!(covers((10, 7), &regions));
+        let regions =
+            Region::decompose_balanced((10, 7), 4, Decomposition::Square, &|x, _| x as f64)
+                .unwrap();
+        assert!(covers((10, 7), &regions));
+
+        // the same runners work with and without a mask
+        let op = |num: &f32, nb: Vec<Option<&f32>>| num + nb.iter().flatten().copied().sum::<f32>();
+        let params = || {
+            IslParams::new(
+                (10, 7),
+                op,
+                4,
+                |x, y| (x * y) as f32,
+                0,
+                0,
+                vec![(-1, 0), (0, 1)],
+                PathBuf::from("raw"),
+            )
+        };
+        let mut plain = Simulation::new(params()).unwrap();
+        let mut masked = Simulation::new(params().with_mask(|_, _| true)).unwrap();
+        plain.step_n(3);
+        masked.step_n(3);
+        assert_eq!(plain.view().to_vec(), masked.view().to_vec());
+    }
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "derive")]
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use rs_isl::{run_isl, IslParams, Value, ValueKind, VtkOutput};
+    use vtkio::{
+        model::{Attribute, DataSet, ElementType, IOBuffer},
+        Vtk,
+    };
+
+    #[derive(Clone, Debug, Default, PartialEq, VtkOutput)]
+    struct Species {
+        density: f32,
+        #[vtk(rename = "alive")]
+        living: bool,
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, VtkOutput)]
+    struct Flow {
+        #[vtk(rename = "p")]
+        pressure: f64,
+        #[vtk(vector)]
+        velocity: [f32; 2],
+        #[vtk(vector)]
+        vorticity: (f32, f32, f32),
+        #[vtk(skip)]
+        #[allow(dead_code)]
+        iterations: usize,
+        species: Species,
+    }
+
+    #[derive(Clone, VtkOutput)]
+    struct Pair(i32, #[vtk(vector)] [u8; 3]);
+
+    #[derive(Clone, VtkOutput)]
+    struct Wrapper<T> {
+        inner: T,
+    }
+
+    fn flow() -> Flow {
+        Flow {
+            pressure: 1.5,
+            velocity: [0.25, -0.5],
+            vorticity: (0.0, 0.0, 2.0),
+            iterations: 12,
+            species: Species {
+                density: 0.75,
+                living: true,
+            },
+        }
+    }
+
+    #[test]
+    fn fields_become_values() {
+        assert_eq!(
+            Flow::value_names(),
+            [
+                "p",
+                "velocity",
+                "vorticity",
+                "species.density",
+                "species.alive"
+            ]
+        );
+        assert_eq!(
+            Flow::value_kinds(),
+            [
+                ValueKind::Scalar,
+                ValueKind::Vector(2),
+                ValueKind::Vector(3),
+                ValueKind::Scalar,
+                ValueKind::Scalar
+            ]
+        );
+        assert_eq!(
+            flow().cellvalue(),
+            [
+                Value::F64(1.5),
+                Value::F32(0.25),
+                Value::F32(-0.5),
+                Value::F32(0.0),
+                Value::F32(0.0),
+                Value::F32(2.0),
+                Value::F32(0.75),
+                Value::U8(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn tuple_and_generic_structs() {
+        assert_eq!(Pair::value_names(), ["0", "1"]);
+        assert_eq!(
+            Pair(-3, [1, 2, 3]).cellvalue(),
+            [Value::I32(-3), Value::U8(1), Value::U8(2), Value::U8(3)]
+        );
+
+        // a single value keeps the name of the field
+        assert_eq!(
+            Wrapper::<Species>::value_names(),
+            ["inner.density", "inner.alive"]
+        );
+        assert_eq!(Wrapper::<u16>::value_names(), ["inner"]);
+        assert_eq!(Wrapper { inner: 4u16 }.cellvalue(), [Value::U16(4)]);
+    }
+
+    #[test]
+    fn derived_output_is_written() {
+        let path = env::temp_dir().join("rs_isl_derive_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let params = IslParams::new(
+            (4, 3),
+            |value: &Flow, _: Vec<Option<&Flow>>| value.clone(),
+            1,
+            |_, _| flow(),
+            1,
+            1,
+            vec![],
+            path.clone(),
+        );
+        run_isl(params).unwrap();
+
+        let vtk = Vtk::import(path.join("ISL0.vtk")).unwrap();
+        let DataSet::StructuredGrid { pieces, .. } = vtk.data else {
+            panic!("expected a structured grid");
+        };
+        let piece = pieces[0].load_piece_data(None).unwrap();
+        let arrays: Vec<_> = piece
+            .data
+            .point
+            .iter()
+            .map(|attribute| match attribute {
+                Attribute::DataArray(array) => array,
+                _ => panic!("expected a data array"),
+            })
+            .collect();
+
+        assert_eq!(arrays.len(), 5);
+        assert!(matches!(&arrays[0].data, IOBuffer::F64(p) if p.iter().all(|p| *p == 1.5)));
+        assert_eq!(arrays[1].elem, ElementType::Vectors);
+        assert!(matches!(&arrays[1].data, IOBuffer::F32(v) if v[..3] == [0.25, -0.5, 0.0]));
+        assert!(matches!(&arrays[4].data, IOBuffer::U8(alive) if alive.iter().all(|a| *a == 1)));
+    }
+}
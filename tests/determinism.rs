@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use rs_isl::{
+        run_isl, Context, Contextual, Decomposition, Executor, IslParams, MultiField,
+        SerialExecutor, Simulation, ThreadExecutor, Tiling, UpdateOrder,
+    };
+
+    const DIM: (usize, usize) = (32, 16);
+    const NEIGHBOURS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    type Op = Contextual<fn(&Context, &f32, Vec<Option<&f32>>) -> f32>;
+    type Init = fn(usize, usize) -> f32;
+
+    // diffusion with a random source, so every cell depends on the numbers drawn everywhere
+    fn noisy(context: &Context, num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let mut rng = context.rng();
+        let sum: f32 = nb.iter().map(|value| value.copied().unwrap_or(*num)).sum();
+        let kick = if rng.chance(0.1) {
+            rng.below(5) as f32
+        } else {
+            0.0
+        };
+        0.6 * num + 0.1 * sum + kick + rng.uniform() as f32 * 0.01
+    }
+
+    fn params(runners: usize, output_path: PathBuf) -> IslParams<f32, Op, Init> {
+        IslParams::new(
+            DIM,
+            Contextual(noisy as fn(&Context, &f32, Vec<Option<&f32>>) -> f32),
+            runners,
+            (|x, y| ((x * 7 + y * 13) % 17) as f32) as Init,
+            12,
+            4,
+            NEIGHBOURS.to_vec(),
+            output_path,
+        )
+        .with_seed(42)
+    }
+
+    fn run(params: IslParams<f32, Op, Init>) -> Vec<Vec<f32>> {
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(12);
+        sim.view().to_vec()
+    }
+
+    fn with_executor(executor: impl Executor + 'static) -> Vec<Vec<f32>> {
+        run(params(1, PathBuf::from("raw")).with_executor(executor))
+    }
+
+    #[test]
+    fn output_files_are_identical_for_any_runner_count() {
+        let root = env::temp_dir().join("rs_isl_determinism_test");
+        let _ = fs::remove_dir_all(&root);
+
+        let mut outputs = vec![];
+        for runners in [1, 2, 4, 8, 16] {
+            let path = root.join(runners.to_string());
+            fs::create_dir_all(&path).unwrap();
+            run_isl(params(runners, path.clone())).unwrap();
+
+            let mut files: Vec<_> = fs::read_dir(&path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect();
+            files.sort();
+            let contents: Vec<_> = files
+                .iter()
+                .map(|file| {
+                    (
+                        file.file_name().unwrap().to_owned(),
+                        fs::read(file).unwrap(),
+                    )
+                })
+                .collect();
+            outputs.push(contents);
+        }
+
+        assert!(!outputs[0].is_empty());
+        for output in &outputs[1..] {
+            assert!(output == &outputs[0]);
+        }
+    }
+
+    #[test]
+    fn results_are_identical_for_any_executor() {
+        let expected = with_executor(SerialExecutor);
+        for runners in [1, 2, 8, 16] {
+            let executor = ThreadExecutor::new(runners).with_decomposition(Decomposition::Auto);
+            assert_eq!(with_executor(executor), expected);
+        }
+        assert_eq!(
+            with_executor(ThreadExecutor::new(4).with_decomposition(Decomposition::Rows)),
+            expected
+        );
+        #[cfg(feature = "rayon")]
+        assert_eq!(with_executor(rs_isl::RayonExecutor::new()), expected);
+
+        let tiled =
+            params(4, PathBuf::from("raw")).with_tiling(Tiling::new((5, 3)).with_time_steps(3));
+        assert_eq!(run(tiled), expected);
+        let balanced = params(4, PathBuf::from("raw"))
+            .with_cost(|x, _| 1.0 + x as f64)
+            .with_rebalancing(2);
+        assert_eq!(run(balanced), expected);
+    }
+
+    #[test]
+    fn coloured_updates_are_identical_for_any_runner_count() {
+        let red_black = |runners| {
+            params(runners, PathBuf::from("raw")).with_update_order(UpdateOrder::RedBlack)
+        };
+        let expected = run(red_black(1));
+        assert_eq!(run(red_black(4)), expected);
+        assert_eq!(run(red_black(8)), expected);
+    }
+
+    #[test]
+    fn seed_selects_the_numbers() {
+        let expected = with_executor(SerialExecutor);
+        let other = run(params(4, PathBuf::from("raw")).with_seed(7));
+        assert_ne!(other, expected);
+        assert_eq!(run(params(2, PathBuf::from("raw")).with_seed(7)), other);
+    }
+
+    #[test]
+    fn coupled_fields_draw_the_same_numbers() {
+        let run = |runners: usize| {
+            let mut sim =
+                MultiField::new(DIM, ThreadExecutor::new(runners), PathBuf::from("raw")).unwrap();
+            sim.set_seed(3);
+            let a = sim.add_field("a", |_, _| 0.0f32, vec![]);
+            let b = sim.add_field("b", |_, _| 0.0f32, vec![]);
+            let draw = || {
+                Contextual(|context: &Context, num: &f32, _: Vec<Option<&f32>>| {
+                    num + context.rng().uniform() as f32
+                })
+            };
+            sim.set_operator(&a, draw()).unwrap();
+            sim.set_operator(&b, draw()).unwrap();
+            sim.step_n(3);
+            (a.view().to_vec(), b.view().to_vec())
+        };
+
+        let (a, b) = run(1);
+        assert_eq!(a, b);
+        assert_eq!(run(4), (a, b));
+    }
+}
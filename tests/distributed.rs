@@ -0,0 +1,345 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::{self, ErrorKind},
+        net::{SocketAddr, TcpListener},
+        path::PathBuf,
+        thread,
+    };
+
+    use rs_isl::{
+        Centring, Context, Contextual, Decomposition, DistributedSimulation, Encode, IslError,
+        IslParams, Simulation, SocketTransport, Transport,
+    };
+    use vtkio::{
+        model::{Attribute, DataSet, IOBuffer, Piece, StructuredGridPiece},
+        Vtk,
+    };
+
+    const DIM: (usize, usize) = (20, 12);
+    // reaches two cells to the left and includes a diagonal
+    const NEIGHBOURS: [(i8, i8); 5] = [(-2, 0), (1, 0), (0, -1), (0, 1), (1, 1)];
+
+    type Op = fn(&f32, Vec<Option<&f32>>) -> f32;
+    type Init = fn(usize, usize) -> f32;
+
+    fn mix(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let weights = [0.3, 0.2, 0.1, 0.15, 0.05];
+        let sum: f32 = nb
+            .iter()
+            .zip(weights)
+            .map(|(value, weight)| value.copied().unwrap_or(1.0) * weight)
+            .sum();
+        0.2 * num + sum
+    }
+
+    fn params(output_path: PathBuf) -> IslParams<f32, Op, Init> {
+        IslParams::new(
+            DIM,
+            mix as Op,
+            1,
+            (|x, y| ((x * 7 + y * 3) % 11) as f32) as Init,
+            6,
+            3,
+            NEIGHBOURS.to_vec(),
+            output_path,
+        )
+    }
+
+    fn serial(steps: usize) -> Vec<Vec<f32>> {
+        let mut sim = Simulation::new(params(PathBuf::from("raw"))).unwrap();
+        sim.step_n(steps);
+        sim.view().to_vec()
+    }
+
+    /// Runs every rank on its own thread and checks its block against the serial result
+    fn check_ranks<C>(transports: Vec<C>, decomposition: Decomposition)
+    where
+        C: Transport + 'static,
+    {
+        let expected = serial(4);
+        let ranks: Vec<_> = transports
+            .into_iter()
+            .map(|transport| {
+                let params = params(PathBuf::from("raw")).with_decomposition(decomposition);
+                thread::spawn(move || {
+                    let mut sim = DistributedSimulation::new(params, transport).unwrap();
+                    sim.step_n(4).unwrap();
+                    let region = sim.region().clone();
+                    let mut values = vec![];
+                    for x in region.x.clone() {
+                        for y in region.y.clone() {
+                            values.push(((x, y), sim.get(x, y)));
+                        }
+                    }
+                    values
+                })
+            })
+            .collect();
+
+        let mut cells = 0;
+        for rank in ranks {
+            for ((x, y), value) in rank.join().unwrap() {
+                assert_eq!(value, expected[x][y], "({x},{y})");
+                cells += 1;
+            }
+        }
+        assert_eq!(cells, DIM.0 * DIM.1);
+    }
+
+    fn tcp(size: usize) -> Vec<SocketTransport<std::net::TcpStream>> {
+        let listeners: Vec<TcpListener> = (0..size)
+            .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+            .collect();
+        let addresses: Vec<SocketAddr> = listeners
+            .iter()
+            .map(|listener| listener.local_addr().unwrap())
+            .collect();
+        let connecting: Vec<_> = listeners
+            .into_iter()
+            .enumerate()
+            .map(|(rank, listener)| {
+                let addresses = addresses.clone();
+                thread::spawn(move || {
+                    SocketTransport::tcp_with_listener(rank, listener, &addresses).unwrap()
+                })
+            })
+            .collect();
+        connecting
+            .into_iter()
+            .map(|rank| rank.join().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ranks_match_a_single_process() {
+        check_ranks(tcp(4), Decomposition::Square);
+        check_ranks(tcp(6), Decomposition::Blocks(3, 2));
+        // strips narrower than the halo
+        check_ranks(tcp(10), Decomposition::Columns);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_sockets() {
+        let dir = env::temp_dir().join("rs_isl_unix_transport_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<PathBuf> = (0..3).map(|rank| dir.join(format!("rank{rank}"))).collect();
+
+        let connecting: Vec<_> = (0..3)
+            .map(|rank| {
+                let paths = paths.clone();
+                thread::spawn(move || SocketTransport::unix(rank, &paths).unwrap())
+            })
+            .collect();
+        let transports = connecting
+            .into_iter()
+            .map(|rank| rank.join().unwrap())
+            .collect();
+        check_ranks(transports, Decomposition::Rows);
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let mut bytes = vec![];
+        1.5f64.encode(&mut bytes);
+        [3u8, 4].encode(&mut bytes);
+        (-7i32).encode(&mut bytes);
+        true.encode(&mut bytes);
+
+        let mut read = bytes.as_slice();
+        assert_eq!(f64::decode(&mut read).unwrap(), 1.5);
+        assert_eq!(<[u8; 2]>::decode(&mut read).unwrap(), [3, 4]);
+        assert_eq!(i32::decode(&mut read).unwrap(), -7);
+        assert!(bool::decode(&mut read).unwrap());
+        assert!(read.is_empty());
+    }
+
+    #[test]
+    fn truncated_values_are_errors() {
+        let error = f64::decode(&mut [0u8; 7].as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(<[u16; 2]>::decode(&mut [0u8; 3].as_slice()).is_err());
+        assert!(bool::decode(&mut [].as_slice()).is_err());
+    }
+
+    /// Answers every receive with the same message
+    struct Replay {
+        rank: usize,
+        size: usize,
+        message: Vec<u8>,
+    }
+
+    impl Transport for Replay {
+        fn rank(&self) -> usize {
+            self.rank
+        }
+
+        fn size(&self) -> usize {
+            self.size
+        }
+
+        fn send(&mut self, _to: usize, _message: &[u8]) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn receive(&mut self, _from: usize) -> io::Result<Vec<u8>> {
+            Ok(self.message.clone())
+        }
+    }
+
+    #[test]
+    fn halo_must_match_its_region() {
+        let step = |message: Vec<u8>| {
+            let transport = Replay {
+                rank: 0,
+                size: 2,
+                message,
+            };
+            let mut sim =
+                DistributedSimulation::new(params(PathBuf::from("raw")), transport).unwrap();
+            sim.step().unwrap_err().kind()
+        };
+
+        // a value cut in half, and more values than the halo holds
+        assert_eq!(step(vec![0; 6]), ErrorKind::InvalidData);
+        assert_eq!(step(vec![0; 4 * DIM.0 * DIM.1]), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn oversized_messages_are_rejected() {
+        let mut transports = tcp(2);
+        let mut receiver = transports.pop().unwrap().with_max_message_len(16);
+        let mut sender = transports.pop().unwrap();
+
+        sender.send(1, &[0; 16]).unwrap();
+        assert_eq!(receiver.receive(0).unwrap().len(), 16);
+        sender.send(1, &[0; 17]).unwrap();
+        assert_eq!(
+            receiver.receive(0).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn rank_must_be_below_the_number_of_addresses() {
+        let addresses: Vec<SocketAddr> = vec!["127.0.0.1:0".parse().unwrap()];
+        let error = SocketTransport::tcp(1, &addresses).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn dt_is_used_and_unsupported_settings_are_rejected() {
+        let single = || Replay {
+            rank: 0,
+            size: 1,
+            message: vec![],
+        };
+        let params = || {
+            let op = Contextual(|context: &Context, num: &f32, _nb: Vec<Option<&f32>>| {
+                num + context.dt as f32
+            });
+            IslParams::new(DIM, op, 1, |_, _| 0.0, 0, 0, vec![], PathBuf::from("raw")).with_dt(0.5)
+        };
+
+        let mut sim = DistributedSimulation::new(params(), single()).unwrap();
+        sim.step_n(3).unwrap();
+        assert_eq!(sim.get(4, 2), 1.5);
+        assert_eq!(sim.time(), 1.5);
+
+        let masked = params().with_mask(|x, _| x > 0);
+        assert!(matches!(
+            DistributedSimulation::new(masked, single()),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+
+    /// Runs 4 ranks writing their output to `path` and loads the last parallel file
+    fn write_pieces(path: PathBuf, centring: Centring) -> Vec<Piece<StructuredGridPiece>> {
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        let ranks: Vec<_> = tcp(4)
+            .into_iter()
+            .map(|transport| {
+                let params = params(path.clone()).with_centring(centring);
+                thread::spawn(move || {
+                    DistributedSimulation::new(params, transport)
+                        .unwrap()
+                        .run()
+                        .unwrap()
+                })
+            })
+            .collect();
+        ranks.into_iter().for_each(|rank| rank.join().unwrap());
+
+        let collection = fs::read_to_string(path.join("ISL.pvd")).unwrap();
+        assert!(collection.contains("timestep=\"6.0\" file=\"ISL2.pvts\""));
+
+        let mut vtk = Vtk::import(path.join("ISL2.pvts")).unwrap();
+        vtk.load_all_pieces().unwrap();
+        let DataSet::StructuredGrid { pieces, .. } = vtk.data else {
+            panic!("expected a structured grid");
+        };
+        assert_eq!(pieces.len(), 4);
+        pieces
+    }
+
+    #[test]
+    fn pieces_form_a_parallel_file() {
+        let path = env::temp_dir().join("rs_isl_distributed_test");
+        let pieces = write_pieces(path, Centring::Point);
+
+        let expected = serial(6);
+        for piece in pieces {
+            let Piece::Inline(piece) = piece else {
+                panic!("expected a loaded piece");
+            };
+            let [x, y, _] = piece.extent.clone().into_ranges();
+            let Attribute::DataArray(array) = &piece.data.point[0] else {
+                panic!("expected a data array");
+            };
+            let IOBuffer::F32(values) = &array.data else {
+                panic!("expected f32 values");
+            };
+
+            let mut values = values.iter();
+            for y in y {
+                for x in x.clone() {
+                    assert_eq!(*values.next().unwrap(), expected[x as usize][y as usize]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cell_pieces_tile_the_domain() {
+        let path = env::temp_dir().join("rs_isl_distributed_cell_test");
+        let pieces = write_pieces(path.clone(), Centring::Cell);
+
+        let parallel = fs::read_to_string(path.join("ISL2.pvts")).unwrap();
+        assert!(parallel.contains("WholeExtent=\"0 20 0 12 0 0\""));
+        assert!(parallel.contains("<PCellData>"));
+
+        // the extents count points, one more than cells along each axis, and only share their borders
+        let mut cells = vec![vec![0; DIM.1]; DIM.0];
+        for piece in pieces {
+            let Piece::Inline(piece) = piece else {
+                panic!("expected a loaded piece");
+            };
+            let [x, y, _] = piece.extent.clone().into_ranges();
+            assert_eq!(
+                piece.num_points(),
+                (x.end() - x.start() + 1) as usize * (y.end() - y.start() + 1) as usize
+            );
+            for x in *x.start()..*x.end() {
+                for y in *y.start()..*y.end() {
+                    cells[x as usize][y as usize] += 1;
+                }
+            }
+        }
+        assert!(cells.iter().flatten().all(|count| *count == 1));
+    }
+}
@@ -10,7 +10,7 @@ mod tests {
         let init = |_x: usize, _y: usize| 0.0;
 
         let params = IslParams::new(
-            (100, 100),
+            (10, 10),
             op,
             17,
             init,
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{Executor, IslParams, SerialExecutor, Simulation, ThreadExecutor};
+
+    // diffusion-like average over the von Neumann neighbourhood
+    fn average(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let (sum, count) = nb
+            .iter()
+            .flatten()
+            .fold((*num, 1.0), |(sum, count), v| (sum + **v, count + 1.0));
+        sum / count
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 7 + y * 13) % 17) as f32
+    }
+
+    fn run(executor: impl Executor + 'static) -> Vec<Vec<f32>> {
+        let params = IslParams::new(
+            (24, 18),
+            average,
+            1,
+            init,
+            0,
+            0,
+            vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            PathBuf::from("raw"),
+        )
+        .with_executor(executor);
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(12);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn executors_agree() {
+        let expected = run(SerialExecutor);
+
+        assert_eq!(run(ThreadExecutor::new(1)), expected);
+        assert_eq!(run(ThreadExecutor::new(6)), expected);
+        assert_eq!(run(ThreadExecutor::new(9)), expected);
+    }
+
+    #[test]
+    fn thread_executor_checks_runners() {
+        let params = IslParams::new(
+            (24, 18),
+            average,
+            1,
+            init,
+            0,
+            0,
+            vec![],
+            PathBuf::from("raw"),
+        )
+        .with_executor(ThreadExecutor::new(19));
+
+        assert!(Simulation::new(params).is_err());
+    }
+}
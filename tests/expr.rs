@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{
+        Expr, IslError, IslParams, Kernel, LinearStencil, MultiField, Operator, SerialExecutor,
+        Simulation,
+    };
+
+    const DIM: (usize, usize) = (16, 12);
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 13 + y * 7) % 11) as f32
+    }
+
+    fn run<F>(op: F, neighbours: Vec<(i8, i8)>, runners: usize) -> Vec<Vec<f32>>
+    where
+        F: Operator<f32> + Clone + 'static,
+    {
+        let params = IslParams::new(
+            DIM,
+            op,
+            runners,
+            init,
+            0,
+            0,
+            neighbours,
+            PathBuf::from("raw"),
+        );
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(5);
+        sim.view().to_vec()
+    }
+
+    fn assert_close(computed: Vec<Vec<f32>>, expected: Vec<Vec<f32>>) {
+        for (computed, expected) in computed.iter().flatten().zip(expected.iter().flatten()) {
+            assert!(
+                (computed - expected).abs() < 1e-4,
+                "{computed} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn printing_and_parsing_round_trip() {
+        let u = Expr::cell;
+        let expr = u(0, 0) * 0.5 - (u(-1, 0) + Expr::field("v", 0, 1)).max(Expr::x() / 2.0)
+            + (-Expr::exists(1, 1)).sin();
+        let printed = expr.to_string();
+        assert_eq!(printed.parse::<Expr>().unwrap(), expr);
+        assert_eq!(printed.parse::<Expr>().unwrap().to_string(), printed);
+
+        let error = "u[0,0] + * 2".parse::<Expr>().unwrap_err();
+        assert_eq!(error.position, 9);
+        assert!("u[0]".parse::<Expr>().is_err());
+        assert!("foo(1)".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn folding_and_offsets() {
+        let expr: Expr = "2 * 3 + u[1,0] * (4 - 3) + u[0,-1] + has[2,2] - 0"
+            .parse()
+            .unwrap();
+        assert_eq!(expr.fold().to_string(), "6.0 + u[1,0] + u[0,-1] + has[2,2]");
+        assert_eq!(expr.offsets(), vec![(1, 0), (0, -1), (2, 2)]);
+    }
+
+    #[test]
+    fn nonlinear_kernel_matches_closure() {
+        let expr: Expr = "max(u[0,0], 0.5 * (u[-1,0] + u[1,0])) + 0.1 * sqrt(abs(u[0,1]))"
+            .parse()
+            .unwrap();
+        let kernel = Kernel::<f32>::new(expr);
+        assert_eq!(kernel.neighbours(), vec![(-1, 0), (1, 0), (0, 1)]);
+
+        // evaluated in f64 like the kernel
+        let closure = |num: &f32, nb: Vec<Option<&f32>>| {
+            let value = |i: usize| nb[i].map_or(0.0, |value| *value as f64);
+            let num = *num as f64;
+            (num.max(0.5 * (value(0) + value(1))) + 0.1 * value(2).abs().sqrt()) as f32
+        };
+        assert_eq!(
+            run(kernel.clone(), kernel.neighbours(), 3),
+            run(closure, kernel.neighbours(), 1)
+        );
+    }
+
+    #[test]
+    fn linear_kernel_runs_as_stencil() {
+        let expr: Expr = "u[0,0] + 0.2 * (u[-1,0] + u[1,0] + u[0,-1] + u[0,1] - 4 * u[0,0]) + 1"
+            .parse()
+            .unwrap();
+        let kernel = Kernel::<f32>::new(expr);
+        let stencil = LinearStencil::new(vec![
+            ((0, 0), 1.0 - 4.0 * 0.2),
+            ((-1, 0), 0.2),
+            ((1, 0), 0.2),
+            ((0, -1), 0.2),
+            ((0, 1), 0.2),
+        ])
+        .with_constant(1.0);
+
+        assert_eq!(kernel.neighbours(), stencil.neighbours());
+        assert_close(
+            run(kernel.clone(), kernel.neighbours(), 4),
+            run(stencil.clone(), stencil.neighbours(), 4),
+        );
+    }
+
+    #[test]
+    fn inlined_field_matches_two_fields() {
+        let laplace: Expr = "u[-1,0] + u[1,0] - 2 * u[0,0]".parse().unwrap();
+        let update: Expr = "$c[0,0] + 0.25 * $lap[0,0]".parse().unwrap();
+
+        // the Laplacian stored in its own field, one step behind
+        let mut sim = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let c = sim.add_field("c", init, vec![(-1, 0), (1, 0)]);
+        let lap = sim.add_field("lap", |_, _| 0.0f32, vec![]);
+        let lap_kernel =
+            Kernel::new("$c[-1,0] + $c[1,0] - 2 * $c[0,0]".parse().unwrap()).with_field(&c);
+        sim.set_operator(&lap, lap_kernel).unwrap();
+        sim.step();
+
+        // fused: the Laplacian is computed where it is read
+        let fused = Kernel::<f32>::new(
+            update
+                .inline("lap", &laplace)
+                .inline("c", &Expr::cell(0, 0)),
+        );
+        assert_eq!(fused.expr().offsets(), vec![(-1, 0), (1, 0)]);
+        let mut expected = c.view().to_vec();
+        let lap = lap.view().to_vec();
+        for x in 0..DIM.0 {
+            for y in 0..DIM.1 {
+                expected[x][y] += 0.25 * lap[x][y];
+            }
+        }
+
+        let params = IslParams::new(
+            DIM,
+            fused.clone(),
+            1,
+            init,
+            0,
+            0,
+            fused.neighbours(),
+            PathBuf::from("raw"),
+        );
+        let mut single = Simulation::new(params).unwrap();
+        single.step();
+        assert_close(single.view().to_vec(), expected);
+    }
+
+    #[test]
+    fn unbound_fields_are_errors() {
+        let kernel = || Kernel::<f32>::new("u[0,0] + $c[0,0]".parse().unwrap());
+        let unbound = |result| matches!(result, Err(IslError::UnboundField(name)) if name == "c");
+
+        let params = IslParams::new(DIM, kernel(), 1, init, 0, 0, vec![], PathBuf::from("raw"));
+        assert!(unbound(Simulation::new(params).map(|_| ())));
+
+        let mut sim = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let u = sim.add_field("u", init, vec![]);
+        let c = sim.add_field("c", init, vec![]);
+        assert!(unbound(sim.set_operator(&u, kernel())));
+        assert!(sim.set_operator(&u, kernel().with_field(&c)).is_ok());
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use rs_isl::{
+        Context, Contextual, IslError, LinearStencil, MultiField, SerialExecutor, ThreadExecutor,
+    };
+
+    const DIM: (usize, usize) = (12, 8);
+
+    // `a` moves to the left, `b` accumulates the value of `a` two cells to the right
+    fn run(runners: usize, steps: usize) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+        let mut sim =
+            MultiField::new(DIM, ThreadExecutor::new(runners), PathBuf::from("raw")).unwrap();
+        let a = sim.add_field("a", |x, y| (x * 3 + y) as f32, vec![(1, 0)]);
+        let b = sim.add_field("b", |_, _| 0.0f32, vec![]);
+
+        sim.set_operator(&a, |num: &f32, nb: Vec<Option<&f32>>| {
+            nb[0].copied().unwrap_or(*num)
+        })
+        .unwrap();
+        let a_handle = a.clone();
+        sim.set_operator(
+            &b,
+            Contextual(
+                move |context: &Context, num: &f32, _nb: Vec<Option<&f32>>| {
+                    num + a_handle.get(context, (2, 0)).unwrap_or(-1.0)
+                },
+            ),
+        )
+        .unwrap();
+
+        sim.step_n(steps);
+        assert_eq!(sim.steps_done(), steps);
+        (a.view().to_vec(), b.view().to_vec())
+    }
+
+    // sequential reference, indexed [x][y]
+    fn reference(steps: usize) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+        let mut a: Vec<Vec<f32>> = (0..DIM.0)
+            .map(|x| (0..DIM.1).map(|y| (x * 3 + y) as f32).collect())
+            .collect();
+        let mut b = vec![vec![0.0; DIM.1]; DIM.0];
+        for _ in 0..steps {
+            let old = a.clone();
+            for x in 0..DIM.0 {
+                for y in 0..DIM.1 {
+                    a[x][y] = old.get(x + 1).map_or(old[x][y], |column| column[y]);
+                    b[x][y] += old.get(x + 2).map_or(-1.0, |column| column[y]);
+                }
+            }
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn coupled_fields_match_reference() {
+        let expected = reference(5);
+        assert_eq!(run(1, 5), expected);
+        assert_eq!(run(4, 5), expected);
+    }
+
+    #[test]
+    fn fields_with_different_types_and_stencils() {
+        let mut sim = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let stencil = LinearStencil::new(vec![((-1, 0), 0.5f32), ((1, 0), 0.5)]);
+        let heat = sim.add_field("heat", |x, _| x as f32, stencil.neighbours());
+        let count = sim.add_field("count", |_, _| 0u8, vec![(-1, -1), (1, 1)]);
+        let fixed = sim.add_field("fixed", |x, y| (x + y) as f32, vec![]);
+
+        sim.set_operator(&heat, stencil).unwrap();
+        sim.set_operator(&count, |num: &u8, nb: Vec<Option<&u8>>| {
+            num + nb.iter().flatten().count() as u8
+        })
+        .unwrap();
+        sim.step_n(2);
+
+        assert_eq!(count.view().get(0, 0), 2);
+        assert_eq!(count.view().get(5, 5), 4);
+        assert_eq!(heat.view().get(3, 2), 3.0);
+        assert_eq!(fixed.view().get(3, 2), 5.0);
+    }
+
+    #[test]
+    fn values_change_between_steps() {
+        let mut sim = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let heat = sim.add_field("heat", |_, _| 0.0f32, vec![(-1, 0)]);
+        sim.set_operator(&heat, |num: &f32, nb: Vec<Option<&f32>>| {
+            nb[0].copied().unwrap_or(*num)
+        })
+        .unwrap();
+
+        sim.view_mut(&heat).set(0, 1, 2.0);
+        sim.step_n(3);
+        assert_eq!(heat.view().get(3, 1), 2.0);
+        assert_eq!(heat.view().get(3, 0), 0.0);
+    }
+
+    #[test]
+    fn every_field_is_exported() {
+        let path = env::temp_dir().join("rs_isl_fields_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let mut sim = MultiField::new(DIM, ThreadExecutor::new(2), path.clone()).unwrap();
+        sim.add_field("pressure", |_, _| 1.0f32, vec![]);
+        sim.add_field("velocity", |_, _| 2.0f32, vec![]);
+        sim.run(4, 2);
+
+        let file = fs::read(path.join("ISL1.vtk")).unwrap();
+        let file = String::from_utf8_lossy(&file);
+        assert!(file.contains("pressure"));
+        assert!(file.contains("velocity"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn unique_names() {
+        let mut sim = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        sim.add_field("a", |_, _| 0.0f32, vec![]);
+        sim.add_field("a", |_, _| 0.0f32, vec![]);
+    }
+
+    #[test]
+    fn operator_offsets_must_match() {
+        let mut sim = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let heat = sim.add_field("heat", |_, _| 0.0f32, vec![(-1, 0)]);
+        assert!(matches!(
+            sim.set_operator(&heat, LinearStencil::new(vec![((1, 0), 1.0f32)])),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn field_of_other_simulation() {
+        let mut first = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let mut second = MultiField::new(DIM, SerialExecutor, PathBuf::from("raw")).unwrap();
+        let a = first.add_field("a", |_, _| 0.0f32, vec![]);
+        second.add_field("a", |_, _| 0.0f32, vec![]);
+        let _ = second.set_operator(&a, |num: &f32, _nb: Vec<Option<&f32>>| *num);
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{Forcing, IslError, IslParams, Region, Simulation, Tiling};
+
+    const DIM: (usize, usize) = (12, 8);
+
+    type Op = fn(&f32, Vec<Option<&f32>>) -> f32;
+    type Init = fn(usize, usize) -> f32;
+
+    // moves every value one cell to the right
+    fn transport(_num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        nb[0].copied().unwrap_or(0.0)
+    }
+
+    fn params(runners: usize, forcing: Forcing<f32>) -> IslParams<f32, Op, Init> {
+        IslParams::new(
+            DIM,
+            transport as Op,
+            runners,
+            (|_, _| 0.0) as Init,
+            0,
+            0,
+            vec![(-1, 0)],
+            PathBuf::from("raw"),
+        )
+        .with_forcing(forcing)
+    }
+
+    fn inlet() -> Forcing<f32> {
+        // the left border is set to the number of the step, counting from one
+        Forcing::new().with_source(
+            Region {
+                x: 0..1,
+                y: 0..DIM.1,
+            },
+            |step, _, value: &mut f32| *value = (step + 1) as f32,
+        )
+    }
+
+    #[test]
+    fn sources_are_applied_after_every_step() {
+        let run = |runners: usize| {
+            let mut sim = Simulation::new(params(runners, inlet())).unwrap();
+            sim.step_n(3);
+            sim.view().to_vec()
+        };
+
+        let result = run(1);
+        // the forced values are transported by the following steps
+        for (column, expected) in result.iter().zip([3.0, 2.0, 1.0, 0.0, 0.0]) {
+            assert!(column.iter().all(|value| *value == expected));
+        }
+        assert_eq!(run(4), result);
+    }
+
+    #[test]
+    fn sources_add_up() {
+        let point = Region { x: 6..7, y: 3..4 };
+        let forcing = inlet()
+            .with_source(point.clone(), |_step, _, value: &mut f32| *value += 10.0)
+            .with_source(point, |step, _, value: &mut f32| *value *= step as f32);
+
+        let mut sim = Simulation::new(params(4, forcing)).unwrap();
+        sim.step_n(2);
+
+        // (0 + 10) * 0 in the first step, (0 + 10) * 1 in the second
+        assert_eq!(sim.view().get(6, 3), 10.0);
+        assert_eq!(sim.view().get(7, 3), 0.0);
+        assert_eq!(sim.view().get(0, 3), 2.0);
+    }
+
+    #[test]
+    fn inactive_cells_are_not_forced() {
+        let params = params(2, inlet()).with_mask(|x, y| x > 0 || y > 3);
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(2);
+
+        assert_eq!(sim.view().get(0, 2), 0.0);
+        assert_eq!(sim.view().get(0, 6), 2.0);
+    }
+
+    #[test]
+    fn no_temporal_blocking() {
+        let params = params(1, inlet()).with_tiling(Tiling::new((4, 4)).with_time_steps(2));
+        assert!(matches!(
+            Simulation::new(params),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn sources_see_the_position() {
+        // a source moving one cell up every step
+        let moving = Forcing::new().with_source(
+            Region {
+                x: 4..5,
+                y: 0..DIM.1,
+            },
+            |step, (_x, y), value: &mut f32| {
+                if y == step {
+                    *value = 1.0
+                }
+            },
+        );
+        let mut sim = Simulation::new(params(2, moving)).unwrap();
+        sim.step_n(3);
+
+        // every value moved to the right by the steps following its forcing
+        let values = sim.view().to_vec();
+        assert_eq!(values[6][0], 1.0);
+        assert_eq!(values[5][1], 1.0);
+        assert_eq!(values[4][2], 1.0);
+        assert_eq!(values.iter().flatten().sum::<f32>(), 3.0);
+    }
+}
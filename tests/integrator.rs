@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{Integrator, IslError, IslParams, Simulation, Tiling};
+
+    type Op = fn(&f32, Vec<Option<&f32>>) -> f32;
+
+    fn growth(num: &f32, _nb: Vec<Option<&f32>>) -> f32 {
+        *num
+    }
+
+    fn laplace(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        nb.iter().map(|n| n.unwrap_or(num) - num).sum()
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 7 + y * 13) % 17) as f32
+    }
+
+    fn params(op: Op, runners: usize) -> IslParams<f32, Op, fn(usize, usize) -> f32> {
+        IslParams::new(
+            (16, 12),
+            op,
+            runners,
+            init as fn(usize, usize) -> f32,
+            0,
+            0,
+            vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            PathBuf::from("raw"),
+        )
+    }
+
+    fn step_once(integrator: Integrator, dt: f64) -> f32 {
+        let params = params(growth, 2).with_integrator(integrator).with_dt(dt);
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step();
+        sim.view().get(3, 2) / init(3, 2)
+    }
+
+    #[test]
+    fn single_step_matches_taylor_series() {
+        // du/dt = u, every scheme matches the series of exp(dt) up to its order
+        let dt = 0.1f32;
+        let taylor = [1.0, dt, dt * dt / 2.0, dt.powi(3) / 6.0, dt.powi(4) / 24.0];
+        let partial = |order: usize| taylor[..=order].iter().sum::<f32>();
+
+        assert_eq!(step_once(Integrator::Euler, 0.1), partial(1));
+        assert!((step_once(Integrator::Rk2, 0.1) - partial(2)).abs() < 1e-6);
+        assert!((step_once(Integrator::SspRk3, 0.1) - partial(3)).abs() < 1e-6);
+        assert!((step_once(Integrator::Rk4, 0.1) - partial(4)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn euler_matches_explicit_update() {
+        let explicit = |num: &f32, nb: Vec<Option<&f32>>| num + 0.1 * laplace(num, nb);
+        let mut sim = Simulation::new(params(explicit, 2)).unwrap();
+        sim.step_n(5);
+        let expected = sim.view().to_vec();
+
+        let params = params(laplace, 2)
+            .with_integrator(Integrator::Euler)
+            .with_dt(0.1);
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(5);
+        let result = sim.view().to_vec();
+
+        for (expected, result) in expected.iter().flatten().zip(result.iter().flatten()) {
+            assert!((expected - result).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn stages_see_neighbours_of_same_stage() {
+        let run = |runners: usize, tiling: Option<Tiling>| {
+            let mut params = params(laplace, runners)
+                .with_integrator(Integrator::Rk4)
+                .with_dt(0.05);
+            if let Some(tiling) = tiling {
+                params = params.with_tiling(tiling);
+            }
+            let mut sim = Simulation::new(params).unwrap();
+            sim.step_n(10);
+            sim.view().to_vec()
+        };
+
+        let expected = run(1, None);
+        assert_eq!(run(4, None), expected);
+        assert_eq!(run(6, Some(Tiling::new((3, 3)))), expected);
+
+        // diffusion keeps the total amount and smooths the field
+        let initial: f32 = (0..16).flat_map(|x| (0..12).map(move |y| init(x, y))).sum();
+        let total: f32 = expected.iter().flatten().sum();
+        assert!((initial - total).abs() < 1e-2);
+    }
+
+    #[test]
+    fn no_temporal_blocking() {
+        let params = params(laplace, 2)
+            .with_integrator(Integrator::Rk2)
+            .with_tiling(Tiling::new((4, 4)).with_time_steps(2));
+        assert!(matches!(
+            Simulation::new(params),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+}
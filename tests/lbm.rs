@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{lbm::*, IslError, IslParams, Simulation};
+
+    const FORCE: f32 = 1e-5;
+    const VISCOSITY: f32 = 1.0 / 6.0;
+
+    fn simulate<H>(
+        params: IslParams<Distributions, Lattice, H>,
+        steps: usize,
+    ) -> Vec<Vec<Distributions>>
+    where
+        H: Fn(usize, usize) -> Distributions,
+    {
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(steps);
+        sim.view().to_vec()
+    }
+
+    fn at_rest(_x: usize, _y: usize) -> Distributions {
+        Distributions::default()
+    }
+
+    /// A channel along x between walls, driven by a force
+    fn channel(width: usize, height: usize) -> Lattice {
+        Lattice::new((width, height), VISCOSITY)
+            .with_edge(Side::Left, Edge::Outflow)
+            .with_edge(Side::Right, Edge::Outflow)
+            .with_force((FORCE, 0.0))
+    }
+
+    /// The velocity profile across a channel along x of the given height, driven by a force
+    fn poiseuille(height: usize) -> f32 {
+        FORCE / (2.0 * VISCOSITY) * height as f32 * height as f32 / 4.0
+    }
+
+    #[test]
+    fn equilibrium_has_its_moments() {
+        let cell = Distributions::equilibrium(1.2, (0.05, -0.02));
+        assert!((cell.density() - 1.2).abs() < 1e-6);
+        let (ux, uy) = cell.velocity();
+        assert!((ux - 0.05).abs() < 1e-6 && (uy + 0.02).abs() < 1e-6);
+        assert_eq!(Lattice::new((4, 4), VISCOSITY).tau(), 1.0);
+    }
+
+    #[test]
+    fn closed_box_conserves_mass() {
+        let lattice = Lattice::new((12, 10), 0.05);
+        let swirl = |x: usize, y: usize| {
+            Distributions::equilibrium(
+                1.0,
+                (
+                    0.02 * (y as f32 - 4.5) / 4.5,
+                    -0.02 * (x as f32 - 5.5) / 5.5,
+                ),
+            )
+        };
+        let cells = simulate(lattice.params(4, swirl, 0, 0, PathBuf::from("raw")), 200);
+        let mass: f32 = cells.iter().flatten().map(Distributions::density).sum();
+        assert!((mass - 120.0).abs() < 1e-3, "{mass}");
+        // the swirl slows down through friction at the walls
+        let speed = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.velocity().0.abs())
+            .fold(0.0, f32::max);
+        assert!(speed < 0.02);
+    }
+
+    #[test]
+    fn force_driven_channel_has_parabolic_profile() {
+        let (width, height) = (4, 12);
+        let lattice = channel(width, height);
+        let cells = simulate(lattice.params(2, at_rest, 0, 0, PathBuf::from("raw")), 2000);
+
+        // halfway bounce-back puts the walls at y = -0.5 and y = height - 0.5
+        let expected = |y: usize| {
+            FORCE / (2.0 * VISCOSITY) * (y as f32 + 0.5) * (height as f32 - 0.5 - y as f32)
+        };
+        for (y, cell) in cells[1].iter().enumerate() {
+            let velocity = cell.velocity().0 - FORCE / 2.0;
+            assert!(
+                (velocity - expected(y)).abs() < 0.02 * poiseuille(height),
+                "{y}: {velocity} {}",
+                expected(y)
+            );
+        }
+    }
+
+    #[test]
+    fn masked_walls_match_domain_walls() {
+        let (width, height) = (4, 12);
+        let lattice = channel(width, height + 2);
+        let params = lattice
+            .params(2, at_rest, 0, 0, PathBuf::from("raw"))
+            .with_mask(move |_, y| y > 0 && y <= height);
+        let masked = simulate(params, 500);
+
+        let lattice = channel(width, height);
+        let walled = simulate(lattice.params(2, at_rest, 0, 0, PathBuf::from("raw")), 500);
+
+        for x in 0..width {
+            for y in 0..height {
+                assert_eq!(masked[x][y + 1], walled[x][y]);
+            }
+        }
+    }
+
+    #[test]
+    fn inlet_flow_leaves_through_outlet() {
+        let (width, height) = (24, 8);
+        let lattice = Lattice::new((width, height), 0.1)
+            .with_edge(Side::Left, Edge::Velocity(0.04, 0.0))
+            .with_edge(Side::Right, Edge::Density(1.0));
+        let run = |runners| {
+            simulate(
+                lattice.params(runners, at_rest, 0, 0, PathBuf::from("raw")),
+                1000,
+            )
+        };
+        let cells = run(4);
+
+        // the same volume passes every cross section
+        for x in [4, 12, 20] {
+            let flux: f32 = cells[x]
+                .iter()
+                .map(|cell| cell.density() * cell.velocity().0)
+                .sum();
+            assert!((flux / height as f32 - 0.04).abs() < 0.004, "{x}: {flux}");
+        }
+        assert_eq!(run(1), cells);
+    }
+
+    #[test]
+    fn lattice_must_match_the_domain() {
+        let mut params = channel(16, 8).params(2, at_rest, 0, 0, PathBuf::from("raw"));
+        params.dimension = (16, 10);
+        assert!(matches!(
+            Simulation::new(params),
+            Err(IslError::DimensionMismatch {
+                expected: (16, 10),
+                found: (16, 8)
+            })
+        ));
+    }
+}
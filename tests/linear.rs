@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{
+        Context, Contextual, IslError, IslParams, LinearStencil, Operator, Simulation, Tiling,
+    };
+
+    fn stencil() -> LinearStencil<f32> {
+        LinearStencil::new(vec![
+            ((0, 0), 0.4),
+            ((-1, 0), 0.15),
+            ((1, 0), 0.15),
+            ((0, -1), 0.1),
+            ((0, 1), 0.1),
+            ((2, -2), 0.05),
+        ])
+        .with_constant(0.25)
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 13 + y * 7) % 11) as f32
+    }
+
+    fn run<F>(op: F, runners: usize, tiling: Option<Tiling>) -> Vec<Vec<f32>>
+    where
+        F: Operator<f32> + Clone + 'static,
+    {
+        let mut params = IslParams::new(
+            (20, 16),
+            op,
+            runners,
+            init,
+            0,
+            0,
+            stencil().neighbours(),
+            PathBuf::from("raw"),
+        );
+        if let Some(tiling) = tiling {
+            params = params.with_tiling(tiling);
+        }
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(9);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn matches_closure() {
+        let weights = stencil();
+        let closure = Contextual(move |context: &Context, num: &f32, nb: Vec<Option<&f32>>| {
+            weights.apply(context, num, nb)
+        });
+        // the same sum, written out by hand
+        let manual = |_num: &f32, nb: Vec<Option<&f32>>| {
+            let weights = [0.4, 0.15, 0.15, 0.1, 0.1, 0.05];
+            nb.iter().zip(weights).fold(0.25, |sum, (nb, w)| match nb {
+                Some(v) => sum + w * **v,
+                None => sum,
+            })
+        };
+
+        let expected = run(manual, 4, None);
+        assert_eq!(run(closure, 4, None), expected);
+        assert_eq!(run(stencil(), 1, None), expected);
+        assert_eq!(run(stencil(), 4, None), expected);
+        assert_eq!(run(stencil(), 4, Some(Tiling::new((3, 5)))), expected);
+        assert_eq!(
+            run(stencil(), 2, Some(Tiling::new((4, 4)).with_time_steps(3))),
+            expected
+        );
+    }
+
+    #[test]
+    fn neighbours_must_match() {
+        let params = IslParams::new(
+            (20, 16),
+            stencil(),
+            1,
+            init,
+            0,
+            0,
+            vec![(1, 0)],
+            PathBuf::from("raw"),
+        );
+        assert!(matches!(
+            Simulation::new(params),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+}
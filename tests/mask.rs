@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use rs_isl::{
+        run_isl, Executor, IslParams, LinearStencil, Region, SerialExecutor, Simulation,
+        ThreadExecutor, Tiling,
+    };
+    use vtkio::{
+        model::{Attribute, DataSet, IOBuffer},
+        Vtk,
+    };
+
+    const DIM: (usize, usize) = (24, 12);
+
+    // a channel with a square obstacle
+    fn active(x: usize, y: usize) -> bool {
+        !((8..12).contains(&x) && (4..8).contains(&y))
+    }
+
+    fn count(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        num + nb.iter().flatten().count() as f32
+    }
+
+    fn run(executor: impl Executor + 'static, tiling: Option<Tiling>) -> Vec<Vec<f32>> {
+        let mut params = IslParams::new(
+            DIM,
+            count,
+            1,
+            |_, _| 0.0f32,
+            0,
+            0,
+            vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            PathBuf::from("raw"),
+        )
+        .with_executor(executor)
+        .with_mask(active);
+        if let Some(tiling) = tiling {
+            params = params.with_tiling(tiling);
+        }
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(3);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn inactive_cells_are_boundary() {
+        let result = run(SerialExecutor, None);
+
+        // inactive cells keep their value
+        assert_eq!(result[9][5], 0.0);
+        // next to the obstacle, like at the border of the domain
+        assert_eq!(result[7][5], 9.0);
+        assert_eq!(result[0][5], 9.0);
+        assert_eq!(result[3][5], 12.0);
+
+        assert_eq!(run(ThreadExecutor::new(4), None), result);
+        assert_eq!(
+            run(ThreadExecutor::new(5), Some(Tiling::new((5, 3)))),
+            result
+        );
+    }
+
+    #[test]
+    fn linear_stencils_respect_the_mask() {
+        let stencil = LinearStencil::new(vec![((-1, 0), 0.5f32), ((1, 0), 0.5)]);
+        let closure = |_num: &f32, nb: Vec<Option<&f32>>| {
+            0.5 * nb[0].copied().unwrap_or(0.0) + 0.5 * nb[1].copied().unwrap_or(0.0)
+        };
+        let init = |x: usize, y: usize| (x * 3 + y) as f32;
+
+        let expected = {
+            let params = IslParams::new(
+                DIM,
+                closure,
+                4,
+                init,
+                0,
+                0,
+                vec![(-1, 0), (1, 0)],
+                "raw".into(),
+            )
+            .with_mask(active);
+            let mut sim = Simulation::new(params).unwrap();
+            sim.step_n(4);
+            sim.view().to_vec()
+        };
+
+        let params = IslParams::new(
+            DIM,
+            stencil.clone(),
+            4,
+            init,
+            0,
+            0,
+            stencil.neighbours(),
+            "raw".into(),
+        )
+        .with_mask(active);
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(4);
+        assert_eq!(sim.view().to_vec(), expected);
+    }
+
+    #[test]
+    fn blocks_balance_active_cells() {
+        // only the left third of the domain is active
+        let cost = |x: usize, _y: usize| if x < 8 { 1.0 } else { 0.0 };
+        let regions = Region::balanced_blocks(DIM, 4, &cost).unwrap();
+
+        assert_eq!(regions.len(), 4);
+        assert_eq!(
+            regions.iter().map(Region::len).sum::<usize>(),
+            DIM.0 * DIM.1
+        );
+        for region in &regions {
+            let active = region.x.clone().filter(|x| *x < 8).count() * region.y.len();
+            assert_eq!(active, 8 * 12 / 4);
+        }
+    }
+
+    #[test]
+    fn inactive_cells_are_written_as_nan() {
+        let path = env::temp_dir().join("rs_isl_mask_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let params = IslParams::new(DIM, count, 2, |_, _| 1.0f32, 1, 1, vec![], path.clone())
+            .with_mask_bitmap(
+                (0..DIM.0)
+                    .map(|x| (0..DIM.1).map(|y| active(x, y)).collect())
+                    .collect(),
+            );
+        run_isl(params).unwrap();
+
+        let vtk = Vtk::import(path.join("ISL0.vtk")).unwrap();
+        let DataSet::StructuredGrid { pieces, .. } = vtk.data else {
+            panic!("expected a structured grid");
+        };
+        let piece = pieces[0].load_piece_data(None).unwrap();
+        let Attribute::DataArray(array) = &piece.data.point[0] else {
+            panic!("expected a data array");
+        };
+        let IOBuffer::F32(values) = &array.data else {
+            panic!("expected f32 values");
+        };
+
+        for y in 0..DIM.1 {
+            for x in 0..DIM.0 {
+                let value = values[y * DIM.0 + x];
+                assert_eq!(value.is_nan(), !active(x, y));
+            }
+        }
+    }
+}
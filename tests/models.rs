@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use std::{f32::consts::PI, path::PathBuf};
+
+    use rs_isl::{models::*, Operator, Simulation, UpdateOrder, VtkOutput};
+
+    fn run<M, H>(model: M, dimension: (usize, usize), init: H, steps: usize) -> Vec<Vec<M::Cell>>
+    where
+        M: Model + Operator<M::Cell> + 'static,
+        M::Cell: VtkOutput + 'static,
+        H: Fn(usize, usize) -> M::Cell,
+    {
+        let params = model.params(dimension, 4, init, 0, 0, PathBuf::from("raw"));
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(steps);
+        sim.view().to_vec()
+    }
+
+    fn alive(cells: &[Vec<bool>]) -> Vec<(usize, usize)> {
+        let mut alive = vec![];
+        for (x, column) in cells.iter().enumerate() {
+            for (y, cell) in column.iter().enumerate() {
+                if *cell {
+                    alive.push((x, y));
+                }
+            }
+        }
+        alive
+    }
+
+    #[test]
+    fn life_rules_parse_and_print() {
+        assert_eq!("B3/S23".parse::<Life>().unwrap(), Life::conway());
+        let high_life: Life = "b36/s23".parse().unwrap();
+        assert_eq!(high_life.to_string(), "B36/S23");
+        assert_eq!("B/S".parse::<Life>().unwrap(), Life::new(&[], &[]));
+        assert!("B9/S23".parse::<Life>().is_err());
+        assert!("S23/B3".parse::<Life>().is_err());
+        assert!("B3S23".parse::<Life>().is_err());
+    }
+
+    #[test]
+    fn glider_moves_diagonally() {
+        let glider =
+            |x: usize, y: usize| matches!((x, y), (2, 1) | (3, 2) | (1, 3) | (2, 3) | (3, 3));
+        let moved = run(Life::conway(), (12, 12), glider, 4);
+        assert_eq!(alive(&moved), vec![(2, 4), (3, 2), (3, 4), (4, 3), (4, 4)]);
+
+        // the glider dies at the boundary instead of wrapping around
+        let crashed = run(Life::conway(), (12, 12), glider, 60);
+        assert!(alive(&crashed).len() <= 4);
+    }
+
+    #[test]
+    fn insulated_heat_is_conserved() {
+        let init = |x: usize, y: usize| if x < 5 && y < 5 { 10.0 } else { 0.0 };
+        let total = |cells: &Vec<Vec<f32>>| cells.iter().flatten().sum::<f32>();
+
+        let insulated = run(Heat::new(0.25), (20, 16), init, 200);
+        assert!((total(&insulated) - 250.0).abs() < 1e-2);
+        let spread = insulated
+            .iter()
+            .flatten()
+            .fold(0.0f32, |max, value| max.max(*value));
+        assert!(spread < 2.0);
+
+        let cooled = run(
+            Heat::new(0.25).with_boundary(Boundary::Fixed(0.0)),
+            (20, 16),
+            init,
+            200,
+        );
+        assert!(total(&cooled) < 0.5 * 250.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn unstable_heat_is_rejected() {
+        Heat::new(0.3);
+    }
+
+    #[test]
+    fn wave_pulse_stays_symmetric() {
+        // a pulse in the middle of the domain along x, uniform along y
+        let init =
+            |x: usize, _: usize| WaveCell::at_rest((-((x as f32 - 20.0).powi(2)) / 8.0).exp());
+        let wave = Wave::new(0.5).with_boundary(Boundary::Reflecting);
+        let cells = run(wave, (41, 8), init, 30);
+        for x in 0..20 {
+            let (left, right) = (cells[x][3].u, cells[40 - x][3].u);
+            assert!((left - right).abs() < 1e-5, "{x}: {left} != {right}");
+        }
+        // the pulse split into two halves running outwards
+        assert!(cells[20][3].u.abs() < 0.1);
+        assert!(cells[5..15].iter().any(|column| column[3].u > 0.3));
+
+        let still = run(Wave::new(0.7), (10, 10), |_, _| WaveCell::default(), 10);
+        assert!(still
+            .iter()
+            .flatten()
+            .all(|cell| *cell == WaveCell::default()));
+    }
+
+    #[test]
+    fn gray_scott_grows_from_a_seed() {
+        let empty = run(
+            GrayScott::mitosis(),
+            (16, 16),
+            |_, _| Chemicals::default(),
+            20,
+        );
+        assert!(empty
+            .iter()
+            .flatten()
+            .all(|cell| *cell == Chemicals::default()));
+
+        let seeded = |x: usize, y: usize| {
+            if (12..20).contains(&x) && (12..20).contains(&y) {
+                Chemicals { u: 0.5, v: 0.25 }
+            } else {
+                Chemicals::default()
+            }
+        };
+        let cells = run(GrayScott::coral(), (32, 32), seeded, 400);
+        let covered = cells.iter().flatten().filter(|cell| cell.v > 0.1).count();
+        assert!(covered > 2 * 64, "{covered}");
+        assert!(cells
+            .iter()
+            .flatten()
+            .all(|cell| (0.0..=1.0).contains(&cell.u)));
+    }
+
+    #[test]
+    fn jacobi_solves_poisson() {
+        const N: usize = 15;
+        let h = 1.0 / (N + 1) as f32;
+        let position = move |i: usize| (i + 1) as f32 * h;
+        let exact = move |x: usize, y: usize| (PI * position(x)).sin() * (PI * position(y)).sin();
+
+        let jacobi = Jacobi::poisson(move |x, y| -2.0 * PI * PI * exact(x, y), h);
+        let params = jacobi
+            .params((N, N), 3, |_, _| 0.0, 0, 0, PathBuf::from("raw"))
+            .with_update_order(UpdateOrder::RedBlack);
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(400);
+        let solution = sim.view().to_vec();
+
+        for (x, column) in solution.iter().enumerate() {
+            for (y, value) in column.iter().enumerate() {
+                // the error of the discretisation is about h² π² / 12
+                assert!((value - exact(x, y)).abs() < 0.01, "({x},{y})");
+            }
+        }
+
+        let laplace = run(
+            Jacobi::laplace().with_boundary(Boundary::Fixed(1.0)),
+            (8, 8),
+            |_, _| 0.0,
+            300,
+        );
+        assert!(laplace
+            .iter()
+            .flatten()
+            .all(|value| (value - 1.0).abs() < 1e-3));
+    }
+}
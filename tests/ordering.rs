@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use rs_isl::{IslError, IslParams, Simulation, ThreadExecutor, Tiling, UpdateOrder};
+
+    const DIM: (usize, usize) = (24, 18);
+
+    // relaxation towards the average of the von Neumann neighbourhood
+    fn average(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let (sum, count) = nb
+            .iter()
+            .flatten()
+            .fold((*num, 1.0), |(sum, count), v| (sum + **v, count + 1.0));
+        sum / count
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 7 + y * 13) % 17) as f32
+    }
+
+    type Op = fn(&f32, Vec<Option<&f32>>) -> f32;
+    type Init = fn(usize, usize) -> f32;
+
+    fn params(runners: usize, order: UpdateOrder) -> IslParams<f32, Op, Init> {
+        IslParams::new(
+            DIM,
+            average as Op,
+            runners,
+            init as Init,
+            0,
+            0,
+            vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            PathBuf::from("raw"),
+        )
+        .with_update_order(order)
+    }
+
+    fn run(runners: usize, order: UpdateOrder, steps: usize) -> Vec<Vec<f32>> {
+        let mut sim = Simulation::new(params(runners, order)).unwrap();
+        sim.step_n(steps);
+        sim.view().to_vec()
+    }
+
+    // sequential red-black sweeps, indexed [x][y]
+    fn red_black_reference(steps: usize) -> Vec<Vec<f32>> {
+        let mut grid: Vec<Vec<f32>> = (0..DIM.0)
+            .map(|x| (0..DIM.1).map(|y| init(x, y)).collect())
+            .collect();
+        let offsets = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+        for _ in 0..steps {
+            for colour in 0..2 {
+                let previous = grid.clone();
+                for x in 0..DIM.0 {
+                    for y in (0..DIM.1).filter(|y| (x + y) % 2 == colour) {
+                        let nb = offsets
+                            .iter()
+                            .map(|(dx, dy)| {
+                                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                                (nx >= 0 && ny >= 0 && nx < DIM.0 as isize && ny < DIM.1 as isize)
+                                    .then(|| &previous[nx as usize][ny as usize])
+                            })
+                            .collect();
+                        grid[x][y] = average(&previous[x][y], nb);
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn red_black_matches_reference() {
+        let expected = red_black_reference(10);
+
+        assert_eq!(run(1, UpdateOrder::RedBlack, 10), expected);
+        assert_eq!(run(4, UpdateOrder::RedBlack, 10), expected);
+        assert_eq!(run(6, UpdateOrder::RedBlack, 10), expected);
+    }
+
+    #[test]
+    fn red_black_differs_from_jacobi() {
+        assert_ne!(
+            run(2, UpdateOrder::RedBlack, 3),
+            run(2, UpdateOrder::Jacobi, 3)
+        );
+    }
+
+    #[test]
+    fn multi_colour_orderings() {
+        let checkerboard = UpdateOrder::MultiColour {
+            colours: 2,
+            colour_of: Arc::new(|x, y| (x + y) % 2),
+        };
+        assert_eq!(run(4, checkerboard, 5), run(4, UpdateOrder::RedBlack, 5));
+
+        let single = UpdateOrder::MultiColour {
+            colours: 1,
+            colour_of: Arc::new(|_, _| 0),
+        };
+        assert_eq!(run(4, single, 5), run(4, UpdateOrder::Jacobi, 5));
+
+        // four colours are independent of the number of runners as well
+        let four = || UpdateOrder::MultiColour {
+            colours: 4,
+            colour_of: Arc::new(|x, y| x % 2 + 2 * (y % 2)),
+        };
+        let expected = run(1, four(), 5);
+        let params = params(1, four()).with_executor(ThreadExecutor::new(6));
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(5);
+        assert_eq!(sim.view().to_vec(), expected);
+    }
+
+    #[test]
+    fn colour_out_of_range() {
+        let order = UpdateOrder::MultiColour {
+            colours: 2,
+            colour_of: Arc::new(|x, _| x % 3),
+        };
+        assert!(matches!(
+            Simulation::new(params(2, order)),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn no_temporal_blocking() {
+        let params =
+            params(2, UpdateOrder::RedBlack).with_tiling(Tiling::new((8, 8)).with_time_steps(2));
+        assert!(matches!(
+            Simulation::new(params),
+            Err(IslError::InvalidParams(_))
+        ));
+    }
+}
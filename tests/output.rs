@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use rs_isl::{
+        run_isl, Centring, IslParams, MultiField, SerialExecutor, Value, ValueKind, VtkOutput,
+    };
+    use vtkio::{
+        model::{Attribute, DataArray, DataSet, ElementType, IOBuffer},
+        Vtk,
+    };
+
+    const DIM: (usize, usize) = (6, 4);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Flow {
+        pressure: f32,
+        velocity: (f32, f32),
+        stress: [f32; 4],
+    }
+
+    impl VtkOutput for Flow {
+        fn value_names() -> Vec<String> {
+            vec!["pressure".into(), "velocity".into(), "stress".into()]
+        }
+
+        fn value_kinds() -> Vec<ValueKind> {
+            vec![
+                ValueKind::Scalar,
+                ValueKind::Vector(2),
+                ValueKind::Tensor(2),
+            ]
+        }
+
+        fn cellvalue(&self) -> Vec<Value> {
+            let mut values = vec![
+                self.pressure.into(),
+                self.velocity.0.into(),
+                self.velocity.1.into(),
+            ];
+            values.extend(self.stress.map(Value::from));
+            values
+        }
+    }
+
+    fn flow(x: usize, y: usize) -> Flow {
+        let (x, y) = (x as f32, y as f32);
+        Flow {
+            pressure: x + y,
+            velocity: (x, -y),
+            stress: [x, 1.0, 2.0, y],
+        }
+    }
+
+    fn keep(value: &Flow, _: Vec<Option<&Flow>>) -> Flow {
+        value.clone()
+    }
+
+    fn import(path: PathBuf) -> Vec<DataArray> {
+        let vtk = Vtk::import(path).unwrap();
+        let DataSet::StructuredGrid { pieces, .. } = vtk.data else {
+            panic!("expected a structured grid");
+        };
+        let piece = pieces[0].load_piece_data(None).unwrap();
+        piece
+            .data
+            .point
+            .into_iter()
+            .map(|attribute| match attribute {
+                Attribute::DataArray(array) => array,
+                _ => panic!("expected a data array"),
+            })
+            .collect()
+    }
+
+    fn values(array: &DataArray) -> &[f32] {
+        let IOBuffer::F32(values) = &array.data else {
+            panic!("expected f32 values");
+        };
+        values
+    }
+
+    #[test]
+    fn vectors_and_tensors_are_padded_to_3d() {
+        let path = env::temp_dir().join("rs_isl_output_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let params = IslParams::new(DIM, keep, 2, flow, 1, 1, vec![], path.clone())
+            .with_mask(|x, y| (x, y) != (5, 3));
+        run_isl(params).unwrap();
+
+        let arrays = import(path.join("ISL0.vtk"));
+        assert_eq!(arrays.len(), 3);
+        assert!(matches!(
+            arrays[0].elem,
+            ElementType::Scalars { num_comp: 1, .. }
+        ));
+        assert_eq!(arrays[1].elem, ElementType::Vectors);
+        assert_eq!(arrays[2].elem, ElementType::Tensors);
+
+        let (velocity, stress) = (values(&arrays[1]), values(&arrays[2]));
+        let cell = 2 * DIM.0 + 3;
+        assert_eq!(&velocity[3 * cell..3 * cell + 3], [3.0, -2.0, 0.0]);
+        assert_eq!(
+            &stress[9 * cell..9 * cell + 9],
+            [3.0, 1.0, 0.0, 2.0, 2.0, 0.0, 0.0, 0.0, 0.0]
+        );
+
+        // every component of an inactive cell is NaN
+        let last = DIM.0 * DIM.1 - 1;
+        assert!(velocity[3 * last..].iter().all(|value| value.is_nan()));
+        assert!(stress[9 * last..].iter().all(|value| value.is_nan()));
+    }
+
+    #[test]
+    fn fields_keep_their_kinds() {
+        let path = env::temp_dir().join("rs_isl_output_fields_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let mut sim = MultiField::new(DIM, SerialExecutor, path.clone()).unwrap();
+        sim.add_field("flow", flow, vec![]);
+        sim.add_field("level", |x, _| x as f32, vec![]);
+        sim.write_output();
+
+        let arrays = import(path.join("ISL0.vtk"));
+        let names: Vec<_> = arrays.iter().map(|array| array.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["flow.pressure", "flow.velocity", "flow.stress", "level"]
+        );
+        assert_eq!(arrays[1].elem, ElementType::Vectors);
+        assert_eq!(values(&arrays[3]).len(), DIM.0 * DIM.1);
+    }
+
+    #[test]
+    fn values_keep_their_type() {
+        let path = env::temp_dir().join("rs_isl_output_types_test");
+        fs::create_dir_all(&path).unwrap();
+
+        let mut sim = MultiField::new(DIM, SerialExecutor, path.clone()).unwrap();
+        sim.add_field("precise", |x, y| 0.1 + (x + y) as f64 * 1e-12, vec![]);
+        sim.add_field("count", |x, y| x as i32 - y as i32, vec![]);
+        sim.add_field("alive", |x, _| x % 2 == 0, vec![]);
+        sim.write_output();
+
+        let arrays = import(path.join("ISL0.vtk"));
+        let IOBuffer::F64(precise) = &arrays[0].data else {
+            panic!("expected f64 values");
+        };
+        assert_eq!(precise[DIM.0 + 2], 0.1 + 3.0 * 1e-12);
+        assert!(matches!(&arrays[1].data, IOBuffer::I32(count) if count[DIM.0] == -1));
+        assert!(matches!(&arrays[2].data, IOBuffer::U8(alive) if alive[..3] == [1, 0, 1]));
+
+        // integers have no NaN, missing cells are written as the largest value
+        let params = IslParams::new(
+            DIM,
+            |v: &u16, _: Vec<Option<&u16>>| *v,
+            2,
+            |_, _| 7,
+            1,
+            1,
+            vec![],
+            path.clone(),
+        )
+        .with_mask(|x, y| (x, y) != (0, 0));
+        run_isl(params).unwrap();
+        let arrays = import(path.join("ISL0.vtk"));
+        assert!(matches!(&arrays[0].data, IOBuffer::U16(values) if values[..2] == [u16::MAX, 7]));
+    }
+
+    #[test]
+    fn cells_surround_their_values() {
+        let path = env::temp_dir().join("rs_isl_output_cells_test");
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        let params = IslParams::new(DIM, keep, 2, flow, 1, 1, vec![], path.clone())
+            .with_centring(Centring::Cell);
+        run_isl(params).unwrap();
+
+        let collection = fs::read_to_string(path.join("ISL.pvd")).unwrap();
+        assert!(collection.contains("file=\"ISL0.vts\""));
+        let file = fs::read_to_string(path.join("ISL0.vts")).unwrap();
+        assert!(file.contains("<PointData/><CellData"));
+        assert!(file.contains("Name=\"velocity\""));
+
+        let vtk = Vtk::import(path.join("ISL0.vts")).unwrap();
+        let DataSet::StructuredGrid { pieces, .. } = vtk.data else {
+            panic!("expected a structured grid");
+        };
+        let piece = pieces[0].load_piece_data(None).unwrap();
+        assert_eq!(
+            piece.extent.into_dims(),
+            [DIM.0 as u32 + 1, DIM.1 as u32 + 1, 1]
+        );
+        let IOBuffer::F32(points) = &piece.points else {
+            panic!("expected f32 points");
+        };
+        assert_eq!(points[..3], [-0.5, -0.5, 0.0]);
+        assert_eq!(points[points.len() - 3..], [5.5, 3.5, 0.0]);
+    }
+
+    #[derive(Clone)]
+    struct Mismatched;
+
+    impl VtkOutput for Mismatched {
+        fn value_names() -> Vec<String> {
+            vec!["velocity".into()]
+        }
+
+        fn value_kinds() -> Vec<ValueKind> {
+            vec![ValueKind::Vector(4)]
+        }
+
+        fn cellvalue(&self) -> Vec<Value> {
+            vec![0.0.into(); 4]
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn vectors_have_2_or_3_components() {
+        let params = IslParams::new(
+            DIM,
+            |_: &Mismatched, _: Vec<Option<&Mismatched>>| Mismatched,
+            1,
+            |_, _| Mismatched,
+            1,
+            1,
+            vec![],
+            env::temp_dir().join("rs_isl_output_mismatch_test"),
+        );
+        run_isl(params).unwrap();
+    }
+}
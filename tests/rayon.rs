@@ -0,0 +1,57 @@
+#![cfg(feature = "rayon")]
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use rs_isl::{IslParams, RayonExecutor, Simulation};
+
+    // diffusion-like average over the von Neumann neighbourhood
+    fn average(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let (sum, count) = nb
+            .iter()
+            .flatten()
+            .fold((*num, 1.0), |(sum, count), v| (sum + **v, count + 1.0));
+        sum / count
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 7 + y * 13) % 17) as f32
+    }
+
+    fn run(executor: Option<RayonExecutor>) -> Vec<Vec<f32>> {
+        let mut params = IslParams::new(
+            (30, 20),
+            average,
+            4,
+            init,
+            0,
+            0,
+            vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            PathBuf::from("raw"),
+        );
+        if let Some(executor) = executor {
+            params = params.with_executor(executor);
+        }
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(15);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn rayon_matches_threads() {
+        let expected = run(None);
+
+        assert_eq!(run(Some(RayonExecutor::new())), expected);
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        let tiled = RayonExecutor::new()
+            .with_pool(Arc::new(pool))
+            .with_tiles((7, 6));
+        assert_eq!(run(Some(tiled)), expected);
+    }
+}
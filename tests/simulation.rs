@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, thread};
+
+    use rs_isl::{Controller, IslParams, Simulation};
+
+    type Op = fn(&f32, Vec<Option<&f32>>) -> f32;
+    type Init = fn(usize, usize) -> f32;
+
+    // every cell takes the value of its left neighbour
+    fn shift(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        nb[0].copied().unwrap_or(*num)
+    }
+
+    fn init(x: usize, _y: usize) -> f32 {
+        x as f32
+    }
+
+    fn shift_params(runners: usize) -> IslParams<f32, Op, Init> {
+        IslParams::new(
+            (12, 8),
+            shift,
+            runners,
+            init,
+            0,
+            0,
+            vec![(-1, 0)],
+            PathBuf::from("raw"),
+        )
+    }
+
+    #[test]
+    fn step_and_view() {
+        let mut sim = Simulation::new(shift_params(4)).unwrap();
+        assert_eq!(sim.view().dimension(), (12, 8));
+
+        sim.step();
+        sim.step_n(4);
+        assert_eq!(sim.steps_done(), 5);
+
+        let view = sim.view();
+        for x in 0..12 {
+            for y in 0..8 {
+                assert_eq!(view.get(x, y), x.saturating_sub(5) as f32);
+            }
+        }
+    }
+
+    #[test]
+    fn modify_between_steps() {
+        let mut sim = Simulation::new(shift_params(6)).unwrap();
+
+        sim.view_mut().set(3, 2, 100.0);
+        sim.step_n(2);
+
+        let values = sim.view().to_vec();
+        assert_eq!(values[5][2], 100.0);
+        assert_eq!(values[5][3], 3.0);
+    }
+
+    #[test]
+    fn invalid_runner_count() {
+        assert!(Simulation::new(shift_params(13)).is_err());
+    }
+
+    #[test]
+    fn pause_between_steps() {
+        let controller = Controller::new();
+
+        // every cell counts the number of steps
+        let op = |num: &f32, _nb: Vec<Option<&f32>>| *num + 1.0;
+        let params = IslParams::new(
+            (12, 8),
+            op,
+            4,
+            |_, _| 0.0,
+            0,
+            0,
+            vec![],
+            PathBuf::from("raw"),
+        )
+        .with_controller(controller.clone());
+        let mut sim = Simulation::new(params).unwrap();
+
+        let pausing = controller.clone();
+        let handle = thread::spawn(move || {
+            let mut grid = pausing.pause().unwrap();
+            grid.set(3, 2, 100.0);
+            grid.step()
+        });
+
+        while !handle.is_finished() {
+            sim.step();
+        }
+        let paused_at = handle.join().unwrap();
+
+        let done = sim.steps_done();
+        assert_eq!(sim.view().get(3, 2), 100.0 + (done - paused_at) as f32);
+        assert_eq!(sim.view().get(4, 2), done as f32);
+
+        drop(sim);
+        assert!(controller.is_finished());
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{
+        Context, Contextual, IslError, IslParams, LinearStencil, Operator, OperatorTable,
+        Simulation, Tiling,
+    };
+
+    const DIM: (usize, usize) = (24, 12);
+    const BULK: u8 = 0;
+    const SOURCE: u8 = 1;
+    const ABSORBING: u8 = 2;
+    const FIXED: u8 = 3;
+
+    fn tag(x: usize, y: usize) -> u8 {
+        match (x, y) {
+            (3..=5, 3..=5) => SOURCE,
+            (20.., _) => ABSORBING,
+            (_, 0) => FIXED,
+            _ => BULK,
+        }
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 7 + y * 13) % 17) as f32
+    }
+
+    fn stencil() -> LinearStencil<f32> {
+        LinearStencil::new(vec![
+            ((0, 0), 0.6),
+            ((-1, 0), 0.1),
+            ((1, 0), 0.1),
+            ((0, -1), 0.1),
+            ((0, 1), 0.1),
+        ])
+    }
+
+    fn source(num: &f32, _nb: Vec<Option<&f32>>) -> f32 {
+        num + 1.0
+    }
+
+    fn absorbing(num: &f32, _nb: Vec<Option<&f32>>) -> f32 {
+        num * 0.5
+    }
+
+    fn run(op: impl Operator<f32> + Clone + 'static, tiling: Option<Tiling>) -> Vec<Vec<f32>> {
+        let mut params = IslParams::new(
+            DIM,
+            op,
+            4,
+            init,
+            0,
+            0,
+            stencil().neighbours(),
+            PathBuf::from("raw"),
+        );
+        if let Some(tiling) = tiling {
+            params = params.with_tiling(tiling);
+        }
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(6);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn table_matches_branching_operator() {
+        let weights = stencil();
+        let branching = Contextual(move |context: &Context, num: &f32, nb: Vec<Option<&f32>>| {
+            match tag(context.x, context.y) {
+                BULK => weights.apply(context, num, nb),
+                SOURCE => source(num, nb),
+                ABSORBING => absorbing(num, nb),
+                _ => *num,
+            }
+        });
+        let expected = run(branching, None);
+
+        let table = OperatorTable::new(DIM, tag)
+            .with_operator(BULK, stencil())
+            .with_operator(SOURCE, source)
+            .with_operator(ABSORBING, absorbing);
+        assert_eq!(table.tag(4, 4), SOURCE);
+
+        assert_eq!(run(table.clone(), None), expected);
+        // tiles inside the bulk take the fast path of the stencil
+        assert_eq!(run(table, Some(Tiling::new((4, 4)))), expected);
+
+        // cells without an operator keep their value
+        assert_eq!(expected[10][0], init(10, 0));
+    }
+
+    #[test]
+    fn tags_from_grid() {
+        let tags = (0..DIM.0)
+            .map(|x| (0..DIM.1).map(|y| tag(x, y)).collect())
+            .collect();
+        let from_grid = OperatorTable::from_tags(tags)
+            .with_operator(BULK, stencil())
+            .with_operator(SOURCE, source);
+        let from_fn = OperatorTable::new(DIM, tag)
+            .with_operator(BULK, stencil())
+            .with_operator(SOURCE, source);
+
+        assert_eq!(run(from_grid, None), run(from_fn, None));
+    }
+
+    #[test]
+    #[should_panic]
+    fn operators_share_offsets() {
+        let _ = OperatorTable::new(DIM, tag)
+            .with_operator(BULK, stencil())
+            .with_operator(SOURCE, LinearStencil::new(vec![((1, 0), 1.0f32)]));
+    }
+
+    #[test]
+    fn tags_must_match_the_domain() {
+        let params = |table: OperatorTable<f32>| {
+            IslParams::new(DIM, table, 4, init, 0, 0, vec![], PathBuf::from("raw"))
+        };
+
+        let small = OperatorTable::new((DIM.0 - 1, DIM.1), tag).with_operator(SOURCE, source);
+        assert!(matches!(
+            Simulation::new(params(small)),
+            Err(IslError::DimensionMismatch {
+                expected: DIM,
+                found: (23, 12)
+            })
+        ));
+
+        let empty = OperatorTable::from_tags(vec![]).with_operator(SOURCE, source);
+        assert!(Simulation::new(params(empty)).is_err());
+    }
+}
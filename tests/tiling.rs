@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rs_isl::{IslParams, Simulation, Tiling};
+
+    // weighted sum over a stencil of radius 2, missing neighbours count as zero
+    fn blur(num: &f32, nb: Vec<Option<&f32>>) -> f32 {
+        let sum: f32 = nb.iter().flatten().copied().sum();
+        0.5 * num + 0.05 * sum
+    }
+
+    fn init(x: usize, y: usize) -> f32 {
+        ((x * 31 + y * 17) % 23) as f32
+    }
+
+    fn run(runners: usize, tiling: Option<Tiling>, steps: usize) -> Vec<Vec<f32>> {
+        let neighbours = vec![(-1, 0), (1, 0), (0, -1), (0, 1), (-2, 0), (0, 2), (1, 1)];
+        let mut params = IslParams::new(
+            (30, 22),
+            blur,
+            runners,
+            init,
+            0,
+            0,
+            neighbours,
+            PathBuf::from("raw"),
+        );
+        if let Some(tiling) = tiling {
+            params = params.with_tiling(tiling);
+        }
+
+        let mut sim = Simulation::new(params).unwrap();
+        sim.step_n(steps);
+        assert_eq!(sim.steps_done(), steps);
+        sim.view().to_vec()
+    }
+
+    #[test]
+    fn tiled_traversal_matches() {
+        let expected = run(4, None, 7);
+        assert_eq!(run(4, Some(Tiling::new((4, 3))), 7), expected);
+        assert_eq!(run(2, Some(Tiling::new((64, 64))), 7), expected);
+    }
+
+    #[test]
+    fn temporal_blocking_matches() {
+        let expected = run(4, None, 7);
+        assert_eq!(
+            run(4, Some(Tiling::new((5, 4)).with_time_steps(3)), 7),
+            expected
+        );
+        assert_eq!(
+            run(1, Some(Tiling::new((8, 8)).with_time_steps(7)), 7),
+            expected
+        );
+        assert_eq!(
+            run(2, Some(Tiling::new((3, 2)).with_time_steps(2)), 7),
+            expected
+        );
+    }
+}